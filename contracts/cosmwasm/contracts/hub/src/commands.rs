@@ -11,7 +11,9 @@ use localmoney_protocol::price::ExecuteMsg::RegisterHub as PriceRegisterHub;
 use localmoney_protocol::profile::ExecuteMsg::RegisterHub as ProfileRegisterHub;
 use localmoney_protocol::trade::ExecuteMsg::RegisterHub as TradeRegisterHub;
 use localmoney_protocol::constants::{
-    MAX_PLATFORM_FEE, MAX_TRADE_DISPUTE_TIMER, MAX_TRADE_EXPIRATION_TIMER,
+    MAX_APPEAL_WINDOW, MAX_ARBITRATION_PANEL_SIZE, MAX_ARBITRATOR_UNBONDING_PERIOD,
+    MAX_DISPUTE_RESOLUTION_TIMER, MAX_PLATFORM_FEE, MAX_TRADE_DISPUTE_TIMER,
+    MAX_TRADE_EXPIRATION_TIMER,
 };
 
 /// Updates the hub configuration
@@ -95,7 +97,7 @@ pub fn update_admin(
 }
 
 /// Validates the hub configuration
-fn validate_config(config: &HubConfig) -> Result<(), ContractError> {
+pub fn validate_config(config: &HubConfig) -> Result<(), ContractError> {
     // Validate platform fees - sum of all fees must be <= MAX_PLATFORM_FEE
     let total_fee = config.chain_fee_pct + config.burn_fee_pct + config.warchest_fee_pct;
     
@@ -105,6 +107,14 @@ fn validate_config(config: &HubConfig) -> Result<(), ContractError> {
         });
     }
 
+    // The expiration bounty is a share of the warchest fee
+    if config.expiration_bounty_pct > Decimal::one() {
+        return Err(ContractError::InvalidParameter {
+            parameter: "expiration_bounty_pct".to_string(),
+            message: Some("Must be <= 100%".to_string()),
+        });
+    }
+
     // Validate timers
     if config.trade_expiration_timer > MAX_TRADE_EXPIRATION_TIMER {
         return Err(ContractError::InvalidParameter {
@@ -124,6 +134,89 @@ fn validate_config(config: &HubConfig) -> Result<(), ContractError> {
         });
     }
 
+    let phase_timers = [
+        ("trade_accept_timer", config.trade_accept_timer),
+        ("trade_funding_timer", config.trade_funding_timer),
+        ("trade_payment_timer", config.trade_payment_timer),
+    ];
+    for (parameter, timer) in phase_timers {
+        if timer > MAX_TRADE_EXPIRATION_TIMER {
+            return Err(ContractError::InvalidParameter {
+                parameter: parameter.to_string(),
+                message: Some(format!("Must be <= {MAX_TRADE_EXPIRATION_TIMER}")),
+            });
+        }
+    }
+
+    if config.trade_release_timer > MAX_TRADE_DISPUTE_TIMER {
+        return Err(ContractError::InvalidParameter {
+            parameter: "trade_release_timer".to_string(),
+            message: Some(format!("Must be <= {MAX_TRADE_DISPUTE_TIMER}")),
+        });
+    }
+
+    if config.dispute_resolution_timer > MAX_DISPUTE_RESOLUTION_TIMER {
+        return Err(ContractError::InvalidParameter {
+            parameter: "dispute_resolution_timer".to_string(),
+            message: Some(format!("Must be <= {MAX_DISPUTE_RESOLUTION_TIMER}")),
+        });
+    }
+
+    if config.appeal_window > MAX_APPEAL_WINDOW {
+        return Err(ContractError::InvalidParameter {
+            parameter: "appeal_window".to_string(),
+            message: Some(format!("Must be <= {MAX_APPEAL_WINDOW}")),
+        });
+    }
+
+    if config.appeal_bond_pct > Decimal::one() {
+        return Err(ContractError::InvalidParameter {
+            parameter: "appeal_bond_pct".to_string(),
+            message: Some("Must be <= 1".to_string()),
+        });
+    }
+
+    if config.overturned_decision_slash > config.arbitrator_bond {
+        return Err(ContractError::InvalidParameter {
+            parameter: "overturned_decision_slash".to_string(),
+            message: Some("Must be <= arbitrator_bond".to_string()),
+        });
+    }
+
+    if config.arbitrator_unbonding_period > MAX_ARBITRATOR_UNBONDING_PERIOD {
+        return Err(ContractError::InvalidParameter {
+            parameter: "arbitrator_unbonding_period".to_string(),
+            message: Some(format!("Must be <= {MAX_ARBITRATOR_UNBONDING_PERIOD}")),
+        });
+    }
+
+    // Disputes draw their arbitrators from the beacon, they can't be opened without one
+    match &config.randomness_beacon {
+        None => {
+            return Err(ContractError::InvalidParameter {
+                parameter: "randomness_beacon".to_string(),
+                message: Some("Is required to draw dispute arbitrators".to_string()),
+            });
+        }
+        Some(beacon) if beacon.public_key.len() != 96 || beacon.period.eq(&0) => {
+            return Err(ContractError::InvalidParameter {
+                parameter: "randomness_beacon".to_string(),
+                message: Some("Needs a compressed G2 public key and a period".to_string()),
+            });
+        }
+        Some(_) => {}
+    }
+
+    // Panels need an odd number of arbitrators to always reach a majority
+    let panel_size = config.arbitration_panel_size;
+    let even_panel = panel_size > 0 && panel_size.is_multiple_of(2);
+    if panel_size > MAX_ARBITRATION_PANEL_SIZE || even_panel {
+        return Err(ContractError::InvalidParameter {
+            parameter: "arbitration_panel_size".to_string(),
+            message: Some(format!("Must be an odd number <= {MAX_ARBITRATION_PANEL_SIZE}")),
+        });
+    }
+
     Ok(())
 }
 
@@ -1,7 +1,9 @@
-use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Response, to_json_binary};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, to_json_binary};
 use cw2::{get_contract_version, set_contract_version};
 
-use crate::state::ADMIN;
+use crate::state::{ADMIN, CONFIG};
 use crate::{commands, queries};
 use localmoney_protocol::errors::ContractError;
 use localmoney_protocol::guards::assert_migration_parameters;
@@ -54,7 +56,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     let previous_contract_version = get_contract_version(deps.storage).unwrap();
 
     assert_migration_parameters(
@@ -66,6 +68,14 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION).unwrap();
     // If the structure of the data in storage changes, we must treat it here
+    // Configs saved before the randomness beacon was required get it from the migration
+    if let Some(mut config) = CONFIG.may_load(deps.storage)? {
+        if config.randomness_beacon.is_none() {
+            config.randomness_beacon = msg.randomness_beacon;
+        }
+        commands::validate_config(&config)?;
+        CONFIG.save(deps.storage, &config)?;
+    }
 
     Ok(Response::default()
         .add_attribute("previous_version", previous_contract_version.version)
@@ -1,18 +1,20 @@
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, SubMsg};
+use cosmwasm_std::{Addr, DepsMut, Env, MessageInfo, Order, Response, StdResult, SubMsg, Uint128};
+use cw_storage_plus::Bound;
 
 use crate::state::OFFERS_COUNT;
 use localmoney_protocol::errors::ContractError;
 use localmoney_protocol::errors::ContractError::HubAlreadyRegistered;
 use localmoney_protocol::guards::{
-    assert_min_g_max, assert_offer_description_valid, assert_ownership,
+    assert_min_g_max, assert_offer_description_valid, assert_offer_expiration_valid,
+    assert_offer_location_valid, assert_offer_schedule_valid, assert_offer_timers_valid,
+    assert_ownership, validate_min_max_items_per_page,
 };
 use localmoney_protocol::hub_utils::{get_hub_config, register_hub_internal};
-use localmoney_protocol::offer::{
-    Offer, OfferModel, OfferMsg, OfferState, OfferUpdateMsg,
-};
+use localmoney_protocol::offer::{offers, Offer, OfferModel, OfferMsg, OfferState, OfferUpdateMsg};
 use localmoney_protocol::profile::{
-    update_profile_active_offers_msg, update_profile_contact_msg,
+    load_profiles_by_addrs, update_profile_active_offers_msg, update_profile_contact_msg, Profile,
 };
+use localmoney_protocol::trade::TradeState;
 
 /// Registers the hub contract address
 pub fn register_hub(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
@@ -34,8 +36,12 @@ pub fn create_offer(
     // Validate input parameters
     assert_min_g_max(msg.min_amount, msg.max_amount)?;
     assert_offer_description_valid(msg.description.clone())?;
+    assert_offer_expiration_valid(msg.expires_at, env.block.time.seconds())?;
+    assert_offer_location_valid(&msg.location, &msg.payment_type)?;
+    assert_offer_schedule_valid(&msg.schedule)?;
 
     let hub_config = get_hub_config(deps.as_ref());
+    assert_offer_timers_valid(&msg.timers, &hub_config)?;
 
     // Load and increment offers count for next sequential ID
     let mut offers_count = OFFERS_COUNT
@@ -64,6 +70,12 @@ pub fn create_offer(
             state: OfferState::Active,
             description: msg.description,
             timestamp: env.block.time.seconds(),
+            expires_at: msg.expires_at,
+            payment_type: msg.payment_type,
+            location: msg.location,
+            schedule: msg.schedule,
+            timers: msg.timers,
+            payment_attestation: msg.payment_attestation,
         },
     )
     .offer;
@@ -102,19 +114,26 @@ pub fn create_offer(
 /// Updates an existing offer
 pub fn update_offer(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: OfferUpdateMsg,
 ) -> Result<Response, ContractError> {
     // Validate input parameters
     assert_min_g_max(msg.min_amount, msg.max_amount)?;
     assert_offer_description_valid(msg.description.clone())?;
+    assert_offer_schedule_valid(&msg.schedule)?;
 
     let hub_config = get_hub_config(deps.as_ref());
+    assert_offer_timers_valid(&msg.timers, &hub_config)?;
     let mut offer_model = OfferModel::may_load(deps.storage, msg.id);
+    // Only a new expiration has to be in the future, an unchanged one can already be past
+    if msg.expires_at.ne(&offer_model.offer.expires_at) {
+        assert_offer_expiration_valid(msg.expires_at, env.block.time.seconds())?;
+    }
 
     // Check ownership
     assert_ownership(info.sender.clone(), offer_model.offer.owner.clone())?;
+    assert_offer_location_valid(&msg.location, &offer_model.offer.payment_type)?;
 
     // Prepare sub-messages for profile updates
     let mut sub_msgs: Vec<SubMsg> = Vec::new();
@@ -146,4 +165,96 @@ pub fn update_offer(
         .add_attribute("action", "update_offer")
         .add_attribute("id", offer.id.to_string())
         .add_attribute("owner", offer.owner.to_string()))
-}
\ No newline at end of file
+}
+
+/// Pauses active offers that have expired or whose maker is no longer present.
+/// Anyone can call it, it only acts on offers that are already hidden from `OffersBy`.
+/// Each call checks a page of active offers, the next page starts after `last_offer_id`.
+pub fn pause_stale_offers(
+    deps: DepsMut,
+    env: Env,
+    start_after: Option<u64>,
+    limit: u32,
+) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
+    let block_time = env.block.time.seconds();
+    let limit = validate_min_max_items_per_page(limit) as usize;
+
+    let active_offers: Vec<Offer> = offers()
+        .idx
+        .state
+        .prefix(OfferState::Active.to_string())
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, offer)| offer))
+        .collect::<StdResult<_>>()?;
+    // The page is full, later offers may still be active
+    let last_offer_id = if active_offers.len() == limit {
+        active_offers.last().map(|offer| offer.id.to_string())
+    } else {
+        None
+    };
+
+    // The makers of the page are loaded with a single query
+    let presence_window = hub_config.maker_presence_window;
+    let profiles: Vec<Profile> = if presence_window.eq(&0) {
+        vec![]
+    } else {
+        let owners: Vec<Addr> = active_offers.iter().map(|offer| offer.owner.clone()).collect();
+        load_profiles_by_addrs(&deps.querier, hub_config.profile_addr.to_string(), owners)?
+    };
+    let mut stale_offers: Vec<Offer> = vec![];
+    for (i, offer) in active_offers.into_iter().enumerate() {
+        let absent = profiles
+            .get(i)
+            .is_some_and(|profile| !profile.is_present(presence_window, block_time));
+        if offer.is_expired(block_time) || absent {
+            stale_offers.push(offer);
+        }
+    }
+
+    let mut sub_msgs: Vec<SubMsg> = Vec::new();
+    let mut paused_ids: Vec<String> = Vec::new();
+    for offer in stale_offers {
+        let mut offer_model = OfferModel::may_load(deps.storage, offer.id);
+        let offer = offer_model.set_state(OfferState::Paused).clone();
+        sub_msgs.push(update_profile_active_offers_msg(
+            hub_config.profile_addr.to_string(),
+            offer.owner.clone(),
+            OfferState::Paused,
+        ));
+        paused_ids.push(offer.id.to_string());
+    }
+
+    Ok(Response::new()
+        .add_submessages(sub_msgs)
+        .add_attribute("action", "pause_stale_offers")
+        .add_attribute("paused_count", paused_ids.len().to_string())
+        .add_attribute("paused_ids", paused_ids.join(","))
+        .add_attribute("last_offer_id", last_offer_id.unwrap_or_default()))
+}
+
+/// Updates the trade statistics of an offer, only callable by the trade contract.
+pub fn update_trade_stats(
+    deps: DepsMut,
+    info: MessageInfo,
+    offer_id: u64,
+    trade_state: TradeState,
+    amount: Uint128,
+    release_time: Option<u64>,
+) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
+    assert_ownership(info.sender, hub_config.trade_addr)?;
+
+    OfferModel::register_trade(deps.storage, offer_id, &trade_state, amount, release_time)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_trade_stats")
+        .add_attribute("offer_id", offer_id.to_string())
+        .add_attribute("trade_state", trade_state.to_string()))
+}
@@ -41,11 +41,22 @@ pub fn execute(
         ExecuteMsg::RegisterHub {} => commands::register_hub(deps, info),
         ExecuteMsg::Create { offer } => commands::create_offer(deps, env, info, offer),
         ExecuteMsg::UpdateOffer { offer_update } => commands::update_offer(deps, env, info, offer_update),
+        ExecuteMsg::PauseStaleOffers { start_after, limit } => {
+            commands::pause_stale_offers(deps, env, start_after, limit)
+        }
+        ExecuteMsg::UpdateTradeStats {
+            offer_id,
+            trade_state,
+            amount,
+            release_time,
+        } => {
+            commands::update_trade_stats(deps, info, offer_id, trade_state, amount, release_time)
+        }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::State {} => to_json_binary(&queries::query_state(deps)?),
         QueryMsg::Offer { id } => to_json_binary(&queries::load_offer_by_id(deps, id)?),
@@ -56,6 +67,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             order,
             limit,
             last,
+            exclude_closed,
         } => to_json_binary(&OfferModel::query_by(
             deps,
             offer_type,
@@ -64,10 +76,24 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             order,
             limit,
             last,
+            exclude_closed.unwrap_or(false),
+            env.block.time.seconds(),
         )?),
         QueryMsg::OffersByOwner { owner, limit, last } => {
             to_json_binary(&OfferModel::query_by_owner(deps, owner, limit, last)?)
         }
+        QueryMsg::OffersByIds { ids } => to_json_binary(&queries::load_offers_by_ids(deps, ids)?),
+        QueryMsg::OffersByLocation {
+            location,
+            limit,
+            last,
+        } => to_json_binary(&OfferModel::query_by_location(
+            deps,
+            location,
+            limit,
+            last,
+            env.block.time.seconds(),
+        )?),
         QueryMsg::OffersCountByStates { states } => {
             to_json_binary(&queries::count_offers_by_states(deps, states)?)
         }
@@ -89,6 +115,10 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
     )?;
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)
         .map_err(ContractError::Std)?;
+    // Offers count by state and fiat are now maintained counters, recount them from storage
+    OfferModel::backfill_counts(deps.storage).map_err(ContractError::Std)?;
+    // Release time samples are no longer returned with the offer stats
+    OfferModel::backfill_release_times(deps.storage).map_err(ContractError::Std)?;
     Ok(Response::new()
         .add_attribute("previous_version", previous_contract_version.version)
         .add_attribute("new_version", CONTRACT_VERSION)
@@ -1,12 +1,13 @@
-use cosmwasm_std::{Deps, StdError, StdResult};
+use cosmwasm_std::{Addr, Deps, Order, StdError, StdResult};
 
 use crate::state::OFFERS_COUNT;
+use localmoney_protocol::guards::validate_batch_query_size;
 use localmoney_protocol::hub_utils::get_hub_config;
 use localmoney_protocol::offer::{
-    offers, OfferResponse, OffersCount, OfferState, FiatOffersCount,
+    offers, OfferModel, OfferResponse, OffersCount, OfferState, FiatOffersCount,
+    OFFERS_COUNT_BY_FIAT_STATE, OFFERS_COUNT_BY_STATE,
 };
-use localmoney_protocol::currencies::FiatCurrency;
-use localmoney_protocol::profile::load_profile;
+use localmoney_protocol::profile::{load_profile, load_profiles_by_addrs, Profile};
 
 /// Queries the current state (offers count)
 pub fn query_state(deps: Deps) -> StdResult<OffersCount> {
@@ -25,52 +26,87 @@ pub fn load_offer_by_id(deps: Deps, id: u64) -> StdResult<OfferResponse> {
         hub_config.profile_addr.to_string(),
         offer.owner.clone(),
     )?;
+    let stats = OfferModel::stats(deps.storage, offer.id);
     
-    Ok(OfferResponse { offer, profile })
+    Ok(OfferResponse {
+        offer,
+        profile,
+        stats,
+    })
+}
+
+/// Loads the offers with the given IDs, resolving all owner profiles with a single query.
+/// IDs that don't match any offer are skipped.
+pub fn load_offers_by_ids(deps: Deps, ids: Vec<u64>) -> StdResult<Vec<OfferResponse>> {
+    validate_batch_query_size(ids.len())?;
+    let hub_config = get_hub_config(deps);
+    let mut found_offers = vec![];
+    for id in ids {
+        if let Some(offer) = offers().may_load(deps.storage, id)? {
+            found_offers.push(offer);
+        }
+    }
+
+    let mut owners: Vec<Addr> = found_offers.iter().map(|offer| offer.owner.clone()).collect();
+    owners.sort();
+    owners.dedup();
+    let profiles = load_profiles_by_addrs(
+        &deps.querier,
+        hub_config.profile_addr.to_string(),
+        owners,
+    )?;
+
+    let result = found_offers
+        .into_iter()
+        .map(|offer| {
+            let profile = profiles
+                .iter()
+                .find(|profile| profile.addr.eq(&offer.owner))
+                .cloned()
+                .unwrap_or(Profile::new(offer.owner.clone(), 0));
+            let stats = OfferModel::stats(deps.storage, offer.id);
+            OfferResponse {
+                offer,
+                profile,
+                stats,
+            }
+        })
+        .collect();
+
+    Ok(result)
 }
 
 /// Counts offers by their states
 pub fn count_offers_by_states(deps: Deps, states: Vec<OfferState>) -> StdResult<OffersCount> {
     let mut count = 0u64;
-    
-    // Iterate through all offers and count those matching the requested states
-    let all_offers = offers()
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending);
-    
-    for result in all_offers {
-        let (_, offer) = result?;
-        if states.contains(&offer.state) {
-            count += 1;
-        }
+    for state in states {
+        count += OFFERS_COUNT_BY_STATE
+            .may_load(deps.storage, &state.to_string())?
+            .unwrap_or_default();
     }
-    
     Ok(OffersCount { count })
 }
 
 /// Counts offers for all fiat currencies by states
 pub fn count_all_fiats_offers(deps: Deps, states: Vec<OfferState>) -> StdResult<Vec<FiatOffersCount>> {
-    use std::collections::HashMap;
-    
-    let mut fiat_counts: HashMap<FiatCurrency, u64> = HashMap::new();
-    
-    // Iterate through all offers and count by fiat currency
-    let all_offers = offers()
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending);
-    
-    for result in all_offers {
-        let (_, offer) = result?;
-        if states.contains(&offer.state) {
-            *fiat_counts.entry(offer.fiat_currency).or_insert(0) += 1;
+    let mut result: Vec<FiatOffersCount> = vec![];
+
+    // The counters are keyed by (fiat, state), so we only go through the fiats that have offers
+    let state_keys: Vec<String> = states.iter().map(|state| state.to_string()).collect();
+    let fiat_counts = OFFERS_COUNT_BY_FIAT_STATE.range(deps.storage, None, None, Order::Ascending);
+    for item in fiat_counts {
+        let ((_, state_key), fiat_count) = item?;
+        if !state_keys.contains(&state_key) || fiat_count.count.eq(&0) {
+            continue;
+        }
+        match result.iter_mut().find(|c| c.fiat.eq(&fiat_count.fiat)) {
+            Some(entry) => entry.count += fiat_count.count,
+            None => result.push(fiat_count),
         }
     }
-    
-    // Convert to vector and sort by count (descending)
-    let mut result: Vec<FiatOffersCount> = fiat_counts
-        .into_iter()
-        .map(|(fiat, count)| FiatOffersCount { fiat, count })
-        .collect();
-    
+
+    // Sort by count (descending)
     result.sort_by(|a, b| b.count.cmp(&a.count));
-    
+
     Ok(result)
-}
\ No newline at end of file
+}
@@ -8,6 +8,7 @@ use localmoney_protocol::errors::ContractError;
 use localmoney_protocol::errors::ContractError::HubAlreadyRegistered;
 use localmoney_protocol::guards::{
     assert_migration_parameters, assert_multiple_ownership, assert_ownership,
+    validate_batch_query_size,
 };
 use localmoney_protocol::hub_utils::{get_hub_config, register_hub_internal};
 use localmoney_protocol::offer::OfferState;
@@ -52,7 +53,8 @@ pub fn execute(
         ExecuteMsg::UpdateActiveOffers {
             profile_addr,
             offer_state,
-        } => update_active_offers(deps, info, profile_addr, offer_state),
+        } => update_active_offers(deps, env, info, profile_addr, offer_state),
+        ExecuteMsg::Heartbeat {} => heartbeat(deps, env, info),
         ExecuteMsg::RegisterHub {} => register_hub(deps, info),
     }
 }
@@ -124,15 +126,24 @@ pub fn update_trades_count(
                 });
             }
         }
+        // RequestExpired is only sent for accepted requests, which were counted as active
         TradeState::EscrowCanceled
+        | TradeState::RequestExpired
         | TradeState::EscrowRefunded
         | TradeState::SettledForMaker
-        | TradeState::SettledForTaker => {
+        | TradeState::SettledForTaker
+        | TradeState::SettledSplit
+        // partially released trades end without counting as a released trade
+        | TradeState::EscrowPartiallyReleased => {
             // decrease active trades when finished
             if profile.active_trades_count > 0 {
                 profile.active_trades_count -= 1;
             }
         }
+        // Sent for the arbitrator that missed the dispute deadline
+        TradeState::ArbitratorReassigned => {
+            profile.missed_dispute_deadlines += 1;
+        }
         TradeState::EscrowReleased => {
             profile.released_trades_count += 1;
             // decrease active trades when finished
@@ -161,6 +172,7 @@ pub fn update_trades_count(
 
 pub fn update_active_offers(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     profile_addr: Addr,
     offer_state: OfferState,
@@ -174,6 +186,8 @@ pub fn update_active_offers(
 
     match offer_state {
         OfferState::Active => {
+            // Activating an offer also counts as a sign of presence
+            profile.last_seen = env.block.time.seconds();
             if profile.active_offers_count < hub_config.active_offers_limit {
                 profile.active_offers_count += 1;
             } else {
@@ -197,24 +211,53 @@ pub fn update_active_offers(
     Ok(Response::default())
 }
 
+// Updates the `last_seen` of the sender's profile, used to hide offers from absent makers
+fn heartbeat(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let storage = deps.storage;
+    let block_time = env.block.time.seconds();
+    let mut profile = ProfileModel::query_profile(storage, info.sender.clone());
+    if profile.created_at.eq(&0) {
+        profile.created_at = block_time
+    }
+    profile.last_seen = block_time;
+    ProfileModel::store(storage, &profile);
+
+    let res = Response::new()
+        .add_attribute("action", "heartbeat")
+        .add_attribute("profile_addr", info.sender.to_string())
+        .add_attribute("last_seen", block_time.to_string());
+    Ok(res)
+}
+
 fn register_hub(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
     register_hub_internal(info.sender, deps.storage, HubAlreadyRegistered {})
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Profile { addr } => {
             to_json_binary(&ProfileModel::query_profile(deps.storage, addr.clone()))
         }
-        QueryMsg::Profiles { limit, start_at } => {
-            to_json_binary(&ProfileModel::query_profiles(deps, env, limit, start_at)?)
+        QueryMsg::Profiles {
+            start_after,
+            limit,
+            order,
+        } => to_json_binary(&ProfileModel::query_profiles(
+            deps,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::ProfilesByAddrs { addrs } => {
+            validate_batch_query_size(addrs.len())?;
+            to_json_binary(&ProfileModel::query_profiles_by_addrs(deps.storage, addrs))
         }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     let previous_contract_version = get_contract_version(deps.storage).unwrap();
 
     assert_migration_parameters(
@@ -226,6 +269,9 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION).unwrap();
     // If the structure of the data in storage changes, we must treat it here
+    // Populates the released trades count and created at indexes for existing profiles
+    ProfileModel::reindex(deps.storage)?;
+    ProfileModel::backfill_last_seen(deps.storage, env.block.time.seconds())?;
 
     Ok(Response::default()
         .add_attribute("previous_version", previous_contract_version.version)
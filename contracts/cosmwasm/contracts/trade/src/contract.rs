@@ -1,12 +1,19 @@
 use cosmwasm_std::{
     coin, entry_point, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, CustomQuery,
-    Decimal, Deps, DepsMut, Env, MessageInfo, Reply, ReplyOn, Response, StdResult, SubMsg, Uint128,
-    Uint256, WasmMsg,
+    Decimal, Deps, DepsMut, Env, MessageInfo, Order, Reply, ReplyOn, Response, StdError,
+    StdResult, Storage, SubMsg, Uint128, Uint256, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
 use std::ops::Sub;
 
 use cw20::Denom;
+use cw_storage_plus::Bound;
+use localmoney_protocol::attestation::{Attestor, PaymentAttestation};
+use localmoney_protocol::constants::{
+    ATTESTOR_DESCRIPTION_LIMIT, EVIDENCE_DESCRIPTION_LIMIT, EVIDENCE_URI_LIMIT,
+    MAX_ARBITRATOR_APPLICATION_ITEMS, MAX_ARBITRATOR_CONFLICTS, MAX_ATTESTORS,
+    MAX_DISPUTE_EVIDENCE_ITEMS,
+};
 use localmoney_protocol::currencies::FiatCurrency;
 use localmoney_protocol::denom_utils::denom_to_string;
 use localmoney_protocol::errors::ContractError;
@@ -15,22 +22,32 @@ use localmoney_protocol::errors::ContractError::{
     OfferNotFound, RefundErrorNotExpired, TradeExpired,
 };
 use localmoney_protocol::guards::{
-    assert_migration_parameters, assert_ownership, assert_sender_is_buyer_or_seller,
-    assert_trade_state_and_type, assert_trade_state_change, assert_trade_state_change_is_valid,
-    assert_value_in_range, validate_min_max_items_per_page,
+    assert_hash_valid, assert_migration_parameters, assert_multiple_ownership, assert_ownership,
+    assert_sender_is_buyer_or_seller, assert_text_length_valid, assert_trade_state_and_type,
+    assert_trade_state_change, assert_trade_state_change_is_valid, assert_value_in_range,
+    validate_min_max_items_per_page,
 };
 use localmoney_protocol::hub::HubConfig;
 use localmoney_protocol::hub_utils::{get_hub_admin, get_hub_config, register_hub_internal};
-use localmoney_protocol::offer::{load_offer, Arbitrator, OfferType, TradeInfo};
+use localmoney_protocol::offer::{
+    load_offer, load_offers_by_ids, update_offer_trade_stats_msg, Arbitrator, OfferResponse,
+    OfferType, TradeInfo,
+};
 use localmoney_protocol::price::{query_fiat_price_for_denom, DenomFiatPrice};
+use localmoney_protocol::randomness::{random_value, randomness_seed, RandomnessBeacon};
+use localmoney_protocol::relay::{secp256k1_pubkey_to_addr, RelayAuthorization, RelayedAction};
 use localmoney_protocol::profile::{
-    load_profile, update_profile_contact_msg, update_profile_trades_count_msg,
+    load_profiles_by_addrs, update_profile_contact_msg, update_profile_trades_count_msg, Profile,
 };
 use localmoney_protocol::trade::{
-    arbitrators, calc_denom_fiat_price, ArbitratorModel, ConversionRoute, ConversionStep,
-    ExecuteMsg, FeeInfo, InstantiateMsg, MigrateMsg, NewTrade, QueryMsg, Swap, SwapMsg, Trade,
-    TradeModel, TradeResponse, TradeState, TradeStateItem, TraderRole, DENOM_CONVERSION_ROUTE,
-    DENOM_CONVERSION_STEP,
+    arbitrators, calc_denom_fiat_price, trades, ArbitratorApplication, ArbitratorAvailability,
+    ArbitratorBond, ArbitratorModel, ConversionRoute, ConversionStep, DisputeAppeal,
+    DisputeCandidate, DisputeDecision, DisputeEvidence, DisputeEvidenceKind, DisputeEvidenceModel,
+    DisputeOutcome, DisputeVote, ExecuteMsg, FeeInfo, InstantiateMsg, MigrateMsg, NewTrade,
+    QueryMsg, Swap, SwapMsg, Trade, TradeModel, TradeResponse, TradeState, TradeStateItem,
+    TradeTimers, TraderRole, TradesFilter, TradesFilteredResponse, ARBITRATOR_APPLICATIONS,
+    ARBITRATOR_AVAILABILITY, ARBITRATOR_BONDS, ARBITRATOR_CONFLICTS, ARBITRATOR_KEY_VERSIONS,
+    ARBITRATOR_STATS, ATTESTORS, DENOM_CONVERSION_ROUTE, DENOM_CONVERSION_STEP, RELAY_NONCES,
 };
 pub const SWAP_REPLY_ID: u64 = 1u64;
 
@@ -81,16 +98,100 @@ pub fn execute(
             arbitrator,
             fiat,
             encryption_key,
-        } => create_arbitrator(deps, info, arbitrator, fiat, encryption_key),
+            weight,
+        } => create_arbitrator(deps, info, arbitrator, fiat, encryption_key, weight),
         ExecuteMsg::DeleteArbitrator { arbitrator, fiat } => {
             delete_arbitrator(deps, info, arbitrator, fiat)
         }
-        ExecuteMsg::SettleDispute { trade_id, winner } => {
-            settle_dispute(deps, env, info, trade_id, winner)
-        }
+        ExecuteMsg::SettleDispute {
+            trade_id,
+            winner,
+            decision_hash,
+        } => settle_dispute(deps, env, info, trade_id, winner, decision_hash),
+        ExecuteMsg::SettleDisputeSplit {
+            trade_id,
+            buyer_share,
+            decision_hash,
+        } => settle_dispute_split(deps, env, info, trade_id, buyer_share, decision_hash),
         ExecuteMsg::RegisterConversionRouteForDenom { denom, route } => {
             register_conversion_route_for_denom(deps, info, denom, route)
         }
+        ExecuteMsg::ExpireTrades { limit } => expire_trades(deps, env, info, limit),
+        ExecuteMsg::ProposeCancel { trade_id } => propose_cancel(deps, env, info, trade_id),
+        ExecuteMsg::ConfirmCancel { trade_id } => confirm_cancel(deps, env, info, trade_id),
+        ExecuteMsg::PartialRelease { trade_id, amount } => {
+            partial_release(deps, env, info, trade_id, amount)
+        }
+        ExecuteMsg::ReassignArbitrator { trade_id } => {
+            reassign_arbitrator(deps, env, info, trade_id)
+        }
+        ExecuteMsg::VoteDispute {
+            trade_id,
+            winner,
+            decision_hash,
+        } => vote_dispute(deps, env, info, trade_id, winner, decision_hash),
+        ExecuteMsg::DrawArbitrator {
+            trade_id,
+            signature,
+        } => draw_arbitrator(deps, env, trade_id, signature),
+        ExecuteMsg::UpdateArbitratorContact { trade_id, contact } => {
+            update_arbitrator_contact(deps, info, trade_id, contact)
+        }
+        ExecuteMsg::SetArbitratorConflicts { arbitrators } => {
+            set_arbitrator_conflicts(deps, info, arbitrators)
+        }
+        ExecuteMsg::ApplyArbitrator {
+            fiats,
+            languages,
+            encryption_key,
+        } => apply_arbitrator(deps, env, info, fiats, languages, encryption_key),
+        ExecuteMsg::ApproveArbitrator { applicant } => approve_arbitrator(deps, info, applicant),
+        ExecuteMsg::RejectArbitrator { applicant } => reject_arbitrator(deps, info, applicant),
+        ExecuteMsg::UnbondArbitrator {} => unbond_arbitrator(deps, env, info),
+        ExecuteMsg::ClaimArbitratorBond {} => claim_arbitrator_bond(deps, env, info),
+        ExecuteMsg::SlashArbitrator {
+            arbitrator,
+            amount,
+            recipient,
+        } => slash_arbitrator(deps, info, arbitrator, amount, recipient),
+        ExecuteMsg::SetArbitratorAvailability {
+            available,
+            max_open_disputes,
+        } => set_arbitrator_availability(deps, info, available, max_open_disputes),
+        ExecuteMsg::RotateArbitratorKey { encryption_key } => {
+            rotate_arbitrator_key(deps, info, encryption_key)
+        }
+        ExecuteMsg::AppealDispute { trade_id } => appeal_dispute(deps, env, info, trade_id),
+        ExecuteMsg::FinalizeSettlement { trade_id } => {
+            finalize_settlement(deps, env, info, trade_id)
+        }
+        ExecuteMsg::SubmitEvidence {
+            trade_id,
+            evidence_hash,
+            uri,
+        } => submit_evidence(deps, env, info, trade_id, evidence_hash, uri),
+        ExecuteMsg::RequestEvidence {
+            trade_id,
+            party,
+            description,
+        } => request_evidence(deps, env, info, trade_id, party, description),
+        ExecuteMsg::NewAttestor {
+            attestor,
+            public_key,
+            description,
+        } => create_attestor(deps, env, info, attestor, public_key, description),
+        ExecuteMsg::DeleteAttestor { attestor } => delete_attestor(deps, info, attestor),
+        ExecuteMsg::SubmitPaymentAttestation {
+            trade_id,
+            signature,
+        } => submit_payment_attestation(deps, env, info, trade_id, signature),
+        ExecuteMsg::RelayAction {
+            action,
+            signer_pubkey,
+            signature,
+            nonce,
+            deadline,
+        } => relay_action(deps, env, info, action, signer_pubkey, signature, nonce, deadline),
     }
 }
 
@@ -107,6 +208,17 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION).unwrap();
     // If the structure of the data in storage changes, we must treat it here
+    // Trades count by state and fiat are now maintained counters, recount them from storage
+    TradeModel::backfill_counts(deps.storage).unwrap();
+    // Buyers and sellers now share a single participant index
+    TradeModel::backfill_participants(deps.storage).unwrap();
+    // Expirable trades are now indexed by their expiration
+    TradeModel::backfill_expiration(deps.storage).unwrap();
+    // Escrows funded by the maker now store the fees sent on top of the amount
+    let hub_config = get_hub_config(deps.as_ref());
+    TradeModel::backfill_escrow_fees(deps.storage, |trade| {
+        calculate_fees(&hub_config, trade.amount).total_fees()
+    })?;
 
     Ok(Response::default()
         .add_attribute("previous_version", previous_contract_version.version)
@@ -139,6 +251,19 @@ fn create_trade(
     let offer = offer_result.offer;
     assert_value_in_range(offer.min_amount, offer.max_amount, new_trade.amount)?;
 
+    // Expired offers can't receive new requests, even if they weren't paused yet
+    if offer.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::OfferExpired {
+            offer_id: offer.id,
+            expired_at: offer.expires_at.unwrap_or_default(),
+        });
+    }
+
+    // Requests outside of the maker's trading hours would expire before they are seen
+    if !offer.is_open(env.block.time.seconds()) {
+        return Err(ContractError::OfferClosed { offer_id: offer.id });
+    }
+
     // Can't create Trade with the same wallet
     if info.sender.eq(&offer.owner) {
         return Err(ContractError::Unauthorized {
@@ -234,37 +359,46 @@ fn create_trade(
         new_trade.profile_taker_encryption_key,
     ));
 
-    let random_seed: u32 = (env.block.time.seconds() % 100) as u32;
-    let arbitrator = ArbitratorModel::get_arbitrator_random(
-        deps.as_ref(),
-        random_seed as usize,
-        offer.fiat_currency.clone(),
-    );
+    // The arbitrator is only drawn if a dispute is opened, the fiat just needs to have one
+    let fiat = offer.fiat_currency.clone();
+    let excluded = dispute_exclusions(deps.storage, &buyer, &seller);
+    if ArbitratorModel::get_arbitrator_random_excluding(deps.as_ref(), 0, fiat.clone(), &excluded)
+        .is_none()
+    {
+        return Err(ContractError::NoArbitratorAvailable { fiat });
+    }
 
-    let expires_at = env.block.time.seconds() + hub_cfg.trade_expiration_timer;
+    // Buy offers wait for the maker to accept the request, sell offers for the maker to fund it
+    let timers = TradeTimers::new(&hub_cfg, &offer.timers);
+    let first_phase_timer = if offer.offer_type == OfferType::Buy {
+        timers.accept
+    } else {
+        timers.funding
+    };
+    let expires_at = env.block.time.seconds() + first_phase_timer;
     //Instantiate Trade state
-    let trade = TradeModel::create(
-        deps.storage,
-        Trade::new(
-            trade_id,
-            env.contract.address.clone(),
-            buyer.clone(),
-            seller.clone(),
-            seller_contact,
-            buyer_contact,
-            arbitrator.arbitrator,
-            hub_cfg.offer_addr.clone(),
-            offer_id,
-            env.block.time.seconds(),
-            expires_at,
-            offer.denom.clone(),
-            new_trade.amount,
-            offer.fiat_currency,
-            denom_final_price,
-            trade_state_history,
-        ),
-    )
-    .trade;
+    let mut trade = Trade::new(
+        trade_id,
+        env.contract.address.clone(),
+        buyer.clone(),
+        seller.clone(),
+        seller_contact,
+        buyer_contact,
+        None,
+        hub_cfg.offer_addr.clone(),
+        offer_id,
+        env.block.time.seconds(),
+        expires_at,
+        offer.denom.clone(),
+        new_trade.amount,
+        offer.fiat_currency,
+        denom_final_price,
+        trade_state_history,
+    );
+    trade.timers = timers;
+    trade.usd_amount = Uint128::try_from(usd_trade_amount).unwrap_or_default();
+    trade.payment_attestation = offer.payment_attestation;
+    let trade = TradeModel::create(deps.storage, trade).trade;
 
     let mut profile_submsgs = create_update_trades_count_msgs(
         hub_cfg.profile_addr.to_string(),
@@ -273,6 +407,13 @@ fn create_trade(
         TradeState::RequestCreated,
     );
     sub_msgs.append(&mut profile_submsgs);
+    sub_msgs.push(update_offer_trade_stats_msg(
+        trade.offer_contract.to_string(),
+        trade.offer_id,
+        TradeState::RequestCreated,
+        trade.amount,
+        None,
+    ));
 
     let denom_str = denom_to_string(&trade.denom);
     let res = Response::new()
@@ -306,12 +447,50 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             deps.storage,
             arbitrator,
         )?),
-        QueryMsg::Arbitrators {} => {
-            to_json_binary(&ArbitratorModel::query_arbitrators(deps.storage)?)
+        QueryMsg::Arbitrators {
+            fiat,
+            start_after,
+            limit,
+        } => to_json_binary(&ArbitratorModel::query_arbitrators(
+            deps.storage,
+            fiat,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::ArbitratorStats { arbitrator } => {
+            to_json_binary(&ArbitratorModel::stats(deps.storage, &arbitrator))
+        }
+        QueryMsg::ArbitratorApplications { start_after, limit } => to_json_binary(
+            &ArbitratorModel::query_applications(deps.storage, start_after, limit)?,
+        ),
+        QueryMsg::ArbitratorBond { arbitrator } => {
+            to_json_binary(&ArbitratorModel::query_bond(deps.storage, arbitrator)?)
+        }
+        QueryMsg::ArbitratorAvailability { arbitrator } => {
+            to_json_binary(&ArbitratorModel::availability(deps.storage, &arbitrator))
         }
         QueryMsg::ArbitratorsFiat { fiat } => to_json_binary(
             &ArbitratorModel::query_arbitrators_fiat(deps.storage, fiat)?,
         ),
+        QueryMsg::ArbitratorConflicts { user } => {
+            to_json_binary(&ArbitratorModel::query_conflicts(deps.storage, user)?)
+        }
+        QueryMsg::DisputeEvidence {
+            trade_id,
+            start_after,
+            limit,
+        } => to_json_binary(&DisputeEvidenceModel::query(
+            deps.storage,
+            trade_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Attestors { start_after, limit } => {
+            to_json_binary(&query_attestors(deps, start_after, limit)?)
+        }
+        QueryMsg::RelayNonce { signer } => {
+            to_json_binary(&RELAY_NONCES.may_load(deps.storage, &signer)?.unwrap_or(0))
+        }
         QueryMsg::TradesCountByStates { states } => {
             to_json_binary(&count_trades_by_states(deps, states)?)
         }
@@ -321,6 +500,34 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::AllFiatsTradesCount { states } => {
             to_json_binary(&count_all_fiats_trades(deps, states)?)
         }
+        QueryMsg::TradesFiltered {
+            user,
+            states,
+            fiat,
+            denom,
+            offer_id,
+            created_after,
+            created_before,
+            start_after,
+            limit,
+        } => {
+            let filter = TradesFilter {
+                states,
+                fiat,
+                denom,
+                offer_id,
+                created_after,
+                created_before,
+            };
+            to_json_binary(&query_trades_filtered(
+                env,
+                deps,
+                user,
+                filter,
+                start_after,
+                limit,
+            )?)
+        }
     }
 }
 
@@ -333,39 +540,12 @@ fn register_hub<T: CustomQuery>(
 
 fn query_trade<T: CustomQuery>(env: Env, deps: Deps<T>, id: u64) -> StdResult<TradeInfo> {
     let hub_config = get_hub_config(deps);
-    let state = TradeModel::from_store(deps.storage, id);
-
-    let buyer = load_profile(
-        &deps.querier,
-        hub_config.profile_addr.to_string(),
-        state.buyer.clone(),
-    )
-    .unwrap();
-
-    let seller = load_profile(
-        &deps.querier,
-        hub_config.profile_addr.to_string(),
-        state.seller.clone(),
-    )
-    .unwrap();
-
-    let arbitrator = load_profile(
-        &deps.querier,
-        hub_config.profile_addr.to_string(),
-        state.arbitrator.clone(),
-    )
-    .unwrap();
+    let trade = trades().load(deps.storage, id)?;
+    let offer_not_found = format!("Offer {} on {}", trade.offer_id, trade.offer_contract);
 
-    let block_time = env.block.time.seconds();
-    let trade = TradeResponse::map(state, buyer, seller, arbitrator, block_time);
-    let offer = load_offer(
-        &deps.querier,
-        trade.offer_id,
-        hub_config.offer_addr.to_string(),
-    )
-    .unwrap();
-
-    Ok(TradeInfo { trade, offer })
+    load_trades_infos(env, deps, &hub_config, vec![trade])?
+        .pop()
+        .ok_or_else(|| StdError::not_found(offer_not_found))
 }
 
 pub fn query_trades<T: CustomQuery>(
@@ -376,7 +556,6 @@ pub fn query_trades<T: CustomQuery>(
     limit: u32,
     last: Option<u64>,
 ) -> StdResult<Vec<TradeInfo>> {
-    let mut trades_infos: Vec<TradeInfo> = vec![];
     let hub_config = get_hub_config(deps);
     let limit = validate_min_max_items_per_page(limit) as usize;
 
@@ -390,40 +569,127 @@ pub fn query_trades<T: CustomQuery>(
     }
     .unwrap();
 
-    trade_results.iter().for_each(|trade: &Trade| {
-        let offer_id = trade.offer_id;
-        let offer_contract = trade.offer_contract.to_string();
-        let offer_response = load_offer(&deps.querier, offer_id, offer_contract).unwrap();
+    load_trades_infos(env, deps, &hub_config, trade_results)
+}
 
-        // TODO change it to make only one query
-        let buyer = load_profile(
-            &deps.querier,
-            hub_config.profile_addr.to_string(),
-            trade.buyer.clone(),
-        )
-        .unwrap();
+pub fn query_trades_filtered<T: CustomQuery>(
+    env: Env,
+    deps: Deps<T>,
+    user: Addr,
+    filter: TradesFilter,
+    start_after: Option<u64>,
+    limit: u32,
+) -> StdResult<TradesFilteredResponse> {
+    let hub_config = get_hub_config(deps);
+    let limit = validate_min_max_items_per_page(limit) as usize;
+    let block_time = env.block.time.seconds();
 
-        let seller = load_profile(
-            &deps.querier,
-            hub_config.profile_addr.to_string(),
-            trade.seller.clone(),
-        )
-        .unwrap();
+    let (trade_results, last_scanned) =
+        TradeModel::trades_filtered(deps.storage, user, &filter, start_after, limit, block_time)?;
 
-        let arbitrator = load_profile(
-            &deps.querier,
-            hub_config.profile_addr.to_string(),
-            trade.arbitrator.clone(),
+    Ok(TradesFilteredResponse {
+        trades: load_trades_infos(env, deps, &hub_config, trade_results)?,
+        last_scanned,
+    })
+}
+
+pub fn query_attestors<T: CustomQuery>(
+    deps: Deps<T>,
+    start_after: Option<Addr>,
+    limit: u32,
+) -> StdResult<Vec<Attestor>> {
+    let limit = validate_min_max_items_per_page(limit) as usize;
+    ATTESTORS
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
         )
-        .unwrap();
+        .take(limit)
+        .map(|item| item.map(|(_, attestor)| attestor))
+        .collect()
+}
 
-        let block_time = env.block.time.seconds();
+// Loads the offers and the profiles of a page of trades with one query to each contract
+fn load_trades_infos<T: CustomQuery>(
+    env: Env,
+    deps: Deps<T>,
+    hub_config: &HubConfig,
+    trade_results: Vec<Trade>,
+) -> StdResult<Vec<TradeInfo>> {
+    if trade_results.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // Trades keep the offer contract they were created against, which may be an earlier one
+    let mut offer_contracts: Vec<Addr> = trade_results
+        .iter()
+        .map(|trade| trade.offer_contract.clone())
+        .collect();
+    offer_contracts.sort();
+    offer_contracts.dedup();
+    let mut offers: Vec<(Addr, OfferResponse)> = vec![];
+    for offer_contract in offer_contracts {
+        let mut offer_ids: Vec<u64> = trade_results
+            .iter()
+            .filter(|trade| trade.offer_contract.eq(&offer_contract))
+            .map(|trade| trade.offer_id)
+            .collect();
+        offer_ids.sort_unstable();
+        offer_ids.dedup();
+        let contract_offers =
+            load_offers_by_ids(&deps.querier, offer_ids, offer_contract.to_string())?;
+        offers.extend(
+            contract_offers
+                .into_iter()
+                .map(|offer| (offer_contract.clone(), offer)),
+        );
+    }
 
-        trades_infos.push(TradeInfo {
-            trade: TradeResponse::map(trade.clone(), buyer, seller, arbitrator, block_time),
-            offer: offer_response,
+    let mut addrs: Vec<Addr> = trade_results
+        .iter()
+        .flat_map(|trade| {
+            let mut addrs = vec![trade.buyer.clone(), trade.seller.clone()];
+            addrs.extend(trade.arbitrator.clone());
+            addrs
         })
-    });
+        .collect();
+    addrs.sort();
+    addrs.dedup();
+    let profiles =
+        load_profiles_by_addrs(&deps.querier, hub_config.profile_addr.to_string(), addrs)?;
+    let find_profile = |addr: &Addr| -> Profile {
+        profiles
+            .iter()
+            .find(|profile| profile.addr.eq(addr))
+            .cloned()
+            .unwrap_or(Profile::new(addr.clone(), 0))
+    };
+
+    let block_time = env.block.time.seconds();
+    let trades_infos = trade_results
+        .into_iter()
+        .filter_map(|trade| {
+            // Offers are never deleted, a missing one means the trade can't be displayed
+            let (_, offer) = offers
+                .iter()
+                .find(|(offer_contract, offer)| {
+                    offer_contract.eq(&trade.offer_contract) && offer.offer.id.eq(&trade.offer_id)
+                })?
+                .clone();
+            let buyer = find_profile(&trade.buyer);
+            let seller = find_profile(&trade.seller);
+            let arbitrator = trade.arbitrator.as_ref().map(find_profile);
+            let key_version = trade
+                .arbitrator
+                .as_ref()
+                .map(|arbitrator| ArbitratorModel::key_version(deps.storage, arbitrator));
+            let trade =
+                TradeResponse::map(trade, buyer, seller, arbitrator, key_version, block_time);
+            Some(TradeInfo { trade, offer })
+        })
+        .collect();
 
     Ok(trades_infos)
 }
@@ -507,6 +773,9 @@ fn fund_escrow(
 
     // Set the state to EscrowFunded and store the trade
     trade.set_state(TradeState::EscrowFunded, &env, &info);
+    trade.escrow_fees = total_fees;
+    // The buyer now has the payment window to deposit the fiat
+    trade.expires_at = env.block.time.seconds() + trade.phase_timers(&hub_config).payment;
     TradeModel::store(deps.storage, &trade).unwrap();
 
     let mut sub_msgs: Vec<SubMsg> = vec![];
@@ -552,17 +821,19 @@ fn accept_request(
     )
     .unwrap();
 
+    // Load Hub Cfg
+    let hub_config = get_hub_config(deps.as_ref());
+
     // Change trade state
     trade.set_state(TradeState::RequestAccepted, &env, &info);
+    // The seller now has the funding window to fund the escrow
+    trade.expires_at = env.block.time.seconds() + trade.phase_timers(&hub_config).funding;
 
     // Set maker contact as buyer
     trade.buyer_contact = Some(maker_contact);
 
     TradeModel::store(deps.storage, &trade).unwrap();
 
-    // Load Hub Cfg
-    let hub_config = get_hub_config(deps.as_ref());
-
     let sub_msgs = create_update_trades_count_msgs(
         hub_config.profile_addr.to_string(),
         trade.buyer.clone(),
@@ -599,8 +870,8 @@ fn fiat_deposited(
 
     // Update trade State to TradeState::FiatDeposited
     trade.set_state(TradeState::FiatDeposited, &env, &info);
-    // Sets the time that will enable the dispute
-    let enables_dispute_at = env.block.time.seconds() + hub_config.trade_dispute_timer;
+    // Sets the time that will enable the dispute, once the seller's release window is over
+    let enables_dispute_at = env.block.time.seconds() + trade.phase_timers(&hub_config).release;
     trade.enables_dispute_at = Some(enables_dispute_at);
 
     TradeModel::store(deps.storage, &trade).unwrap();
@@ -665,6 +936,13 @@ fn cancel_request(
         trade.set_state(TradeState::RequestCanceled, &env, &info);
     }
     TradeModel::store(deps.storage, &trade).unwrap();
+    sub_msgs.push(update_offer_trade_stats_msg(
+        trade.offer_contract.to_string(),
+        trade.offer_id,
+        trade.get_state(),
+        trade.amount,
+        None,
+    ));
 
     let res = Response::new()
         .add_attribute("action", "cancel_request")
@@ -679,8 +957,7 @@ fn release_escrow(
     trade_id: u64,
 ) -> Result<Response, ContractError> {
     // Load trade and validate that permission and state are valid.
-    let mut trade = TradeModel::from_store(deps.storage, trade_id);
-    let trade_denom = denom_to_string(&trade.denom);
+    let trade = TradeModel::from_store(deps.storage, trade_id);
     if trade.seller.eq(&info.sender) {
         assert_trade_state_change_is_valid(
             trade.get_state(),
@@ -695,6 +972,133 @@ fn release_escrow(
         });
     }
 
+    release_to_buyer(deps, env, info, trade, "release_escrow")
+}
+
+/// Lets the buyer release the escrow with a payment attestation of a registered attestor,
+/// once the seller let its release window pass. Only trades of opted-in offers accept it.
+fn submit_payment_attestation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade_id: u64,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    let trade = TradeModel::from_store(deps.storage, trade_id);
+    assert_ownership(info.sender.clone(), trade.buyer.clone())?;
+    if !trade.payment_attestation {
+        return Err(ContractError::PaymentAttestationDisabled { trade_id });
+    }
+    assert_trade_state_change_is_valid(
+        trade.get_state(),
+        TradeState::FiatDeposited,
+        TradeState::EscrowReleased,
+    )?;
+
+    let releasable_at = trade.enables_dispute_at.unwrap_or_default();
+    if env.block.time.seconds() < releasable_at {
+        return Err(ContractError::PaymentAttestationTooEarly { releasable_at });
+    }
+
+    let attestation = PaymentAttestation {
+        chain_id: env.block.chain_id.clone(),
+        trade_contract: env.contract.address.clone(),
+        trade_id,
+        buyer: trade.buyer.clone(),
+        seller: trade.seller.clone(),
+        fiat: trade.fiat.clone(),
+    };
+    let mut attestor: Option<Addr> = None;
+    for item in ATTESTORS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, registered) = item?;
+        // A malformed signature or key of one attestor must not stop the others from matching
+        let verified = attestation.verify(deps.api, &signature, &registered.public_key);
+        if verified.unwrap_or(false) {
+            attestor = Some(registered.attestor);
+            break;
+        }
+    }
+    let attestor = attestor.ok_or(ContractError::InvalidPaymentAttestation { trade_id })?;
+
+    let res = release_to_buyer(deps, env, info, trade, "submit_payment_attestation")?;
+    Ok(res.add_attribute("attestor", attestor.to_string()))
+}
+
+/// Runs a trade action signed off-chain by a trader, the relayer pays the gas.
+/// Each signer's nonce must be used in order, so an authorization can only run once.
+#[allow(clippy::too_many_arguments)]
+fn relay_action(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: RelayedAction,
+    signer_pubkey: Binary,
+    signature: Binary,
+    nonce: u64,
+    deadline: u64,
+) -> Result<Response, ContractError> {
+    // None of the relayed actions take funds, they would be stuck in the contract
+    if !info.funds.is_empty() {
+        return Err(InvalidParameter {
+            parameter: "funds".to_string(),
+            message: Some("Relayed actions don't accept funds.".to_string()),
+        });
+    }
+
+    let signer = secp256k1_pubkey_to_addr(deps.api, &signer_pubkey).map_err(|_| {
+        InvalidParameter {
+            parameter: "signer_pubkey".to_string(),
+            message: Some("Must be a compressed secp256k1 public key.".to_string()),
+        }
+    })?;
+    let authorization = RelayAuthorization {
+        chain_id: env.block.chain_id.clone(),
+        trade_contract: env.contract.address.clone(),
+        action: action.clone(),
+        nonce,
+        deadline,
+    };
+    let expected_nonce = RELAY_NONCES.may_load(deps.storage, &signer)?.unwrap_or(0);
+    authorization.authorize(
+        deps.api,
+        env.block.time.seconds(),
+        &signature,
+        &signer_pubkey,
+        expected_nonce,
+    )?;
+    RELAY_NONCES.save(deps.storage, &signer, &(nonce + 1))?;
+
+    let relayer = info.sender;
+    let info = MessageInfo {
+        sender: signer.clone(),
+        funds: vec![],
+    };
+    let res = match action {
+        RelayedAction::AcceptRequest {
+            trade_id,
+            maker_contact,
+        } => accept_request(deps, env, info, trade_id, maker_contact),
+        RelayedAction::FiatDeposited { trade_id } => fiat_deposited(deps, env, info, trade_id),
+        RelayedAction::ReleaseEscrow { trade_id } => release_escrow(deps, env, info, trade_id),
+        RelayedAction::CancelRequest { trade_id } => cancel_request(deps, env, info, trade_id),
+    }?;
+    Ok(res
+        .add_attribute("relayer", relayer.to_string())
+        .add_attribute("signer", signer.to_string())
+        .add_attribute("nonce", nonce.to_string()))
+}
+
+/// Releases the whole escrow to the buyer, fees are deducted if the buyer is the maker.
+fn release_to_buyer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut trade: Trade,
+    action: &str,
+) -> Result<Response, ContractError> {
+    let trade_id = trade.id;
+    let trade_denom = denom_to_string(&trade.denom);
+
     // Load HubConfig
     let hub_config = get_hub_config(deps.as_ref());
 
@@ -734,6 +1138,21 @@ fn release_escrow(
     );
     send_msgs.append(&mut profile_submsgs);
 
+    // Time the seller took to release the escrow after the fiat was deposited
+    let release_time = trade
+        .state_history
+        .iter()
+        .rev()
+        .find(|item| item.state.eq(&TradeState::FiatDeposited))
+        .map(|item| env.block.time.seconds() - item.timestamp);
+    send_msgs.push(update_offer_trade_stats_msg(
+        trade.offer_contract.to_string(),
+        trade.offer_id,
+        TradeState::EscrowReleased,
+        trade.amount,
+        release_time,
+    ));
+
     // Send tokens to buyer
     send_msgs.push(SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
         to_address: trade.buyer.to_string(),
@@ -742,7 +1161,7 @@ fn release_escrow(
 
     let res = Response::new()
         .add_submessages(send_msgs)
-        .add_attribute("action", "release_escrow")
+        .add_attribute("action", action)
         .add_attribute("trade_id", trade_id.to_string())
         .add_attribute("state", trade.get_state().clone().to_string())
         .add_attribute("trade_denom", denom_to_string(&trade.denom))
@@ -750,6 +1169,105 @@ fn release_escrow(
     Ok(res)
 }
 
+/// Releases part of the escrow to the buyer and refunds the rest to the seller.
+/// Fees are only charged on the released amount.
+fn partial_release(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade_id: u64,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let mut trade = TradeModel::from_store(deps.storage, trade_id);
+    assert_ownership(info.sender.clone(), trade.seller.clone())?;
+    assert_trade_state_change_is_valid(
+        trade.get_state(),
+        TradeState::FiatDeposited,
+        TradeState::EscrowPartiallyReleased,
+    )?;
+
+    // A full release must go through ReleaseEscrow
+    if amount.is_zero() || amount >= trade.amount {
+        return Err(InvalidParameter {
+            parameter: "amount".to_string(),
+            message: Some(format!("Must be between 1 and {}.", trade.amount - Uint128::one())),
+        });
+    }
+
+    let hub_config = get_hub_config(deps.as_ref());
+
+    trade.set_state(TradeState::EscrowPartiallyReleased, &env, &info);
+    trade.released_amount = Some(amount);
+    TradeModel::store(deps.storage, &trade)?;
+
+    let offer_response = load_offer(
+        &deps.querier,
+        trade.offer_id,
+        trade.offer_contract.to_string(),
+    )?;
+
+    let trade_denom = denom_to_string(&trade.denom);
+    let mut send_msgs: Vec<SubMsg> = Vec::new();
+    let fee_info = add_protocol_fees_msgs(
+        deps,
+        &mut send_msgs,
+        &amount,
+        trade_denom.clone(),
+        &hub_config,
+    );
+
+    // Fees are deducted from the release when the maker is the buyer, otherwise the maker sent
+    // them on top of the escrow and gets back what wasn't charged
+    let mut release_amount = amount;
+    let mut refund_amount = trade.amount - amount;
+    if trade.buyer.eq(&offer_response.offer.owner) {
+        release_amount = release_amount.sub(fee_info.total_fees());
+    } else {
+        refund_amount += trade.escrow_fees.saturating_sub(fee_info.total_fees());
+    }
+
+    let mut profile_submsgs = create_update_trades_count_msgs(
+        hub_config.profile_addr.to_string(),
+        trade.buyer.clone(),
+        trade.seller.clone(),
+        TradeState::EscrowPartiallyReleased,
+    );
+    send_msgs.append(&mut profile_submsgs);
+
+    // Time the seller took to release the escrow after the fiat was deposited
+    let release_time = trade
+        .state_history
+        .iter()
+        .rev()
+        .find(|item| item.state.eq(&TradeState::FiatDeposited))
+        .map(|item| env.block.time.seconds() - item.timestamp);
+    send_msgs.push(update_offer_trade_stats_msg(
+        trade.offer_contract.to_string(),
+        trade.offer_id,
+        TradeState::EscrowPartiallyReleased,
+        amount,
+        release_time,
+    ));
+
+    send_msgs.push(SubMsg::new(create_send_msg(
+        trade.buyer.clone(),
+        vec![Coin::new(release_amount.u128(), trade_denom.clone())],
+    )));
+    send_msgs.push(SubMsg::new(create_send_msg(
+        trade.seller.clone(),
+        vec![Coin::new(refund_amount.u128(), trade_denom.clone())],
+    )));
+
+    let res = Response::new()
+        .add_submessages(send_msgs)
+        .add_attribute("action", "partial_release")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("state", trade.get_state().to_string())
+        .add_attribute("released_amount", release_amount.to_string())
+        .add_attribute("refunded_amount", refund_amount.to_string());
+    Ok(res)
+}
+
 fn refund_escrow(
     deps: DepsMut,
     env: Env,
@@ -779,6 +1297,7 @@ fn refund_escrow(
     }
 
     let mut trade: Trade = TradeModel::from_store(deps.storage, trade_id);
+    let previous_state = trade.get_state();
 
     //Update trade state to TradeState::EscrowRefunded
     trade.set_state(TradeState::EscrowRefunded, &env, &info);
@@ -793,7 +1312,19 @@ fn refund_escrow(
         TradeState::EscrowRefunded,
     );
 
-    let amount = trade.amount;
+    // Canceled escrows were already counted in the offer stats
+    if previous_state.eq(&TradeState::EscrowFunded) {
+        sub_msgs.push(update_offer_trade_stats_msg(
+            trade.offer_contract.to_string(),
+            trade.offer_id,
+            TradeState::EscrowRefunded,
+            trade.amount,
+            None,
+        ));
+    }
+
+    // No protocol fee is taken, the fees sent by the maker are refunded as well
+    let amount = trade.amount + trade.escrow_fees;
     let denom = denom_to_string(&trade.denom);
     let refund_amount = vec![Coin::new(amount.u128(), denom.clone())];
     sub_msgs.push(SubMsg::new(create_send_msg(trade.seller, refund_amount)));
@@ -803,6 +1334,198 @@ fn refund_escrow(
     Ok(res)
 }
 
+/// Proposes to cancel a funded trade, the counterparty has to confirm it with `ConfirmCancel`.
+fn propose_cancel(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade_id: u64,
+) -> Result<Response, ContractError> {
+    let mut trade = TradeModel::from_store(deps.storage, trade_id);
+    assert_sender_is_buyer_or_seller(
+        info.sender.clone(),
+        trade.buyer.clone(),
+        trade.seller.clone(),
+    )?;
+    assert_trade_state_change(
+        trade.get_state(),
+        vec![TradeState::EscrowFunded, TradeState::FiatDeposited],
+        TradeState::EscrowRefunded,
+    )?;
+
+    trade.cancel_proposed_by = Some(info.sender.clone());
+    trade.state_history.push(TradeStateItem {
+        actor: info.sender.clone(),
+        state: TradeState::CancelProposed,
+        timestamp: env.block.time.seconds(),
+    });
+    TradeModel::store(deps.storage, &trade)?;
+
+    let res = Response::new()
+        .add_attribute("action", "propose_cancel")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("proposed_by", info.sender.to_string());
+    Ok(res)
+}
+
+/// Confirms the cancel proposed by the counterparty and refunds the seller, fees included.
+fn confirm_cancel(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade_id: u64,
+) -> Result<Response, ContractError> {
+    let mut trade = TradeModel::from_store(deps.storage, trade_id);
+    assert_sender_is_buyer_or_seller(
+        info.sender.clone(),
+        trade.buyer.clone(),
+        trade.seller.clone(),
+    )?;
+    assert_trade_state_change(
+        trade.get_state(),
+        vec![TradeState::EscrowFunded, TradeState::FiatDeposited],
+        TradeState::EscrowRefunded,
+    )?;
+
+    // The proposal must come from the other party
+    match &trade.cancel_proposed_by {
+        Some(proposer) if proposer.ne(&info.sender) => {}
+        _ => return Err(ContractError::CancelNotProposed { trade_id }),
+    }
+
+    trade.set_state(TradeState::EscrowRefunded, &env, &info);
+    TradeModel::store(deps.storage, &trade)?;
+
+    let hub_config = get_hub_config(deps.as_ref());
+    let mut sub_msgs: Vec<SubMsg> = create_update_trades_count_msgs(
+        hub_config.profile_addr.to_string(),
+        trade.buyer.clone(),
+        trade.seller.clone(),
+        TradeState::EscrowRefunded,
+    );
+    sub_msgs.push(update_offer_trade_stats_msg(
+        trade.offer_contract.to_string(),
+        trade.offer_id,
+        TradeState::EscrowCanceled,
+        trade.amount,
+        None,
+    ));
+
+    // No protocol fee is taken, the fees sent by the maker are refunded as well
+    let refund_amount = trade.amount + trade.escrow_fees;
+    let denom = denom_to_string(&trade.denom);
+    sub_msgs.push(SubMsg::new(create_send_msg(
+        trade.seller.clone(),
+        vec![Coin::new(refund_amount.u128(), denom)],
+    )));
+
+    let res = Response::new()
+        .add_submessages(sub_msgs)
+        .add_attribute("action", "confirm_cancel")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("state", trade.get_state().to_string())
+        .add_attribute("refund_amount", refund_amount.to_string());
+    Ok(res)
+}
+
+/// Stores the expired state of trades past their expiration, anyone can call it.
+/// Funded escrows are refunded to the seller and the caller gets a share of the warchest fee
+/// held by maker funded escrows as a bounty.
+fn expire_trades(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: u32,
+) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
+    let block_time = env.block.time.seconds();
+    let limit = validate_min_max_items_per_page(limit) as usize;
+
+    let expired_trades = TradeModel::expired_trades(deps.storage, block_time, limit)?;
+
+    let mut sub_msgs: Vec<SubMsg> = vec![];
+    let mut bounty: Vec<Coin> = vec![];
+    let mut expired_ids: Vec<String> = vec![];
+    let mut refunded_ids: Vec<String> = vec![];
+    for mut trade in expired_trades {
+        let previous_state = trade.get_state();
+        if previous_state.eq(&TradeState::EscrowFunded) {
+            trade.set_state(TradeState::EscrowRefunded, &env, &info);
+            TradeModel::store(deps.storage, &trade)?;
+
+            let mut profile_submsgs = create_update_trades_count_msgs(
+                hub_config.profile_addr.to_string(),
+                trade.buyer.clone(),
+                trade.seller.clone(),
+                TradeState::EscrowRefunded,
+            );
+            sub_msgs.append(&mut profile_submsgs);
+            sub_msgs.push(update_offer_trade_stats_msg(
+                trade.offer_contract.to_string(),
+                trade.offer_id,
+                TradeState::EscrowRefunded,
+                trade.amount,
+                None,
+            ));
+
+            // The bounty is taken from the fees the maker sent on top of the amount, if any
+            let denom = denom_to_string(&trade.denom);
+            let mut bounty_amount = Uint128::zero();
+            if !trade.escrow_fees.is_zero() {
+                let warchest_amount = calculate_fees(&hub_config, trade.amount).warchest_amount;
+                let one_e18 = Decimal::one().atomics();
+                bounty_amount = warchest_amount
+                    .multiply_ratio(hub_config.expiration_bounty_pct.atomics(), one_e18);
+                if !bounty_amount.is_zero() {
+                    match bounty.iter_mut().find(|c| c.denom.eq(&denom)) {
+                        Some(c) => c.amount += bounty_amount,
+                        None => bounty.push(Coin::new(bounty_amount.u128(), denom.clone())),
+                    }
+                }
+            }
+
+            // No protocol fee is taken, the fees sent by the maker are refunded minus the bounty
+            let refund_amount = trade.amount + trade.escrow_fees.saturating_sub(bounty_amount);
+            let refund_amount = vec![Coin::new(refund_amount.u128(), denom)];
+            sub_msgs.push(SubMsg::new(create_send_msg(trade.seller.clone(), refund_amount)));
+            refunded_ids.push(trade.id.to_string());
+        } else {
+            trade.set_state(TradeState::RequestExpired, &env, &info);
+            TradeModel::store(deps.storage, &trade)?;
+
+            // Accepted requests were counted as active trades on both profiles
+            if previous_state.eq(&TradeState::RequestAccepted) {
+                let mut profile_submsgs = create_update_trades_count_msgs(
+                    hub_config.profile_addr.to_string(),
+                    trade.buyer.clone(),
+                    trade.seller.clone(),
+                    TradeState::RequestExpired,
+                );
+                sub_msgs.append(&mut profile_submsgs);
+            }
+            sub_msgs.push(update_offer_trade_stats_msg(
+                trade.offer_contract.to_string(),
+                trade.offer_id,
+                TradeState::RequestExpired,
+                trade.amount,
+                None,
+            ));
+            expired_ids.push(trade.id.to_string());
+        }
+    }
+
+    if !bounty.is_empty() {
+        sub_msgs.push(SubMsg::new(create_send_msg(info.sender.clone(), bounty)));
+    }
+
+    let res = Response::new()
+        .add_submessages(sub_msgs)
+        .add_attribute("action", "expire_trades")
+        .add_attribute("expired_ids", expired_ids.join(","))
+        .add_attribute("refunded_ids", refunded_ids.join(","));
+    Ok(res)
+}
+
 //region arbitration
 pub fn create_arbitrator(
     deps: DepsMut,
@@ -810,16 +1533,26 @@ pub fn create_arbitrator(
     arbitrator_address: Addr,
     fiat: FiatCurrency,
     encryption_key: String,
+    weight: Option<u32>,
 ) -> Result<Response, ContractError> {
     let admin = get_hub_admin(deps.as_ref()).addr;
     let hub_config = get_hub_config(deps.as_ref());
     assert_ownership(info.sender, admin)?;
 
+    // Registering an arbitrator again updates its weight
+    if weight.eq(&Some(0)) {
+        return Err(InvalidParameter {
+            parameter: "weight".to_string(),
+            message: Some("Must be greater than 0.".to_string()),
+        });
+    }
     ArbitratorModel::create_arbitrator(
         deps.storage,
         Arbitrator {
             arbitrator: arbitrator_address.clone(),
             fiat: fiat.clone(),
+            weight,
+            languages: vec![],
         },
     );
 
@@ -835,107 +1568,1141 @@ pub fn create_arbitrator(
         .add_attribute("action", "create_arbitrator")
         .add_attribute("arbitrator", arbitrator_address.to_string())
         .add_attribute("asset", fiat.to_string())
+        .add_attribute("encryption_key", encryption_key)
+        .add_attribute("weight", weight.unwrap_or(1).to_string());
+
+    Ok(res)
+}
+
+pub fn delete_arbitrator(
+    deps: DepsMut,
+    info: MessageInfo,
+    arbitrator: Addr,
+    fiat: FiatCurrency,
+) -> Result<Response, ContractError> {
+    let admin = get_hub_admin(deps.as_ref());
+    assert_ownership(info.sender, admin.addr)?;
+
+    let index = arbitrator.clone().to_string() + &fiat.to_string();
+
+    arbitrators().remove(deps.storage, &index).unwrap();
+
+    let res = Response::new()
+        .add_attribute("action", "delete_arbitrator")
+        .add_attribute("arbitrator", arbitrator.to_string())
+        .add_attribute("asset", fiat.to_string());
+
+    Ok(res)
+}
+
+/// Lets an arbitrator pause new disputes or cap how many it decides at once.
+/// Disputes already assigned to it are not affected.
+fn set_arbitrator_availability(
+    deps: DepsMut,
+    info: MessageInfo,
+    available: bool,
+    max_open_disputes: Option<u32>,
+) -> Result<Response, ContractError> {
+    if ArbitratorModel::query_arbitrator(deps.storage, info.sender.clone())?.is_empty() {
+        return Err(ContractError::ArbitratorNotFound {
+            arbitrator: info.sender,
+        });
+    }
+
+    let availability = ArbitratorAvailability {
+        available,
+        max_open_disputes,
+    };
+    ARBITRATOR_AVAILABILITY.save(deps.storage, &info.sender, &availability)?;
+
+    let max_open_disputes = max_open_disputes.map(|max| max.to_string()).unwrap_or_default();
+    let res = Response::new()
+        .add_attribute("action", "set_arbitrator_availability")
+        .add_attribute("arbitrator", info.sender.to_string())
+        .add_attribute("available", available.to_string())
+        .add_attribute("max_open_disputes", max_open_disputes);
+    Ok(res)
+}
+
+/// Publishes a new encryption key for the sender. The parties of its open disputes see their
+/// contacts' key version fall behind and re-send them with `UpdateArbitratorContact`.
+fn rotate_arbitrator_key(
+    deps: DepsMut,
+    info: MessageInfo,
+    encryption_key: String,
+) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
+    // Arbitrators that are unbonding still decide their open disputes
+    let open_disputes = ArbitratorModel::open_disputes(deps.storage, &info.sender);
+    let entries = ArbitratorModel::query_arbitrator(deps.storage, info.sender.clone())?;
+    if entries.is_empty() && open_disputes.eq(&0) {
+        return Err(ContractError::ArbitratorNotFound {
+            arbitrator: info.sender,
+        });
+    }
+
+    let key_version = ArbitratorModel::key_version(deps.storage, &info.sender) + 1;
+    ARBITRATOR_KEY_VERSIONS.save(deps.storage, &info.sender, &key_version)?;
+
+    let update_profile_sub_msg = update_profile_contact_msg(
+        hub_config.profile_addr.to_string(),
+        info.sender.clone(),
+        "N/A".to_string(),
+        encryption_key.clone(),
+    );
+
+    let res = Response::new()
+        .add_submessage(update_profile_sub_msg)
+        .add_attribute("action", "rotate_arbitrator_key")
+        .add_attribute("arbitrator", info.sender.to_string())
+        .add_attribute("key_version", key_version.to_string())
+        .add_attribute("open_disputes", open_disputes.to_string())
         .add_attribute("encryption_key", encryption_key);
+    Ok(res)
+}
+
+/// Applies to become an arbitrator, bonding the required amount of the hub local denom.
+fn apply_arbitrator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    fiats: Vec<FiatCurrency>,
+    languages: Vec<String>,
+    encryption_key: String,
+) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
+    if ARBITRATOR_APPLICATIONS.has(deps.storage, &info.sender) {
+        return Err(ContractError::ArbitratorApplicationPending {
+            applicant: info.sender,
+        });
+    }
+
+    let max_items = MAX_ARBITRATOR_APPLICATION_ITEMS;
+    if fiats.is_empty() || fiats.len() > max_items || languages.len() > max_items {
+        return Err(InvalidParameter {
+            parameter: "fiats".to_string(),
+            message: Some(format!("Between 1 and {max_items} fiats, up to {max_items} languages.")),
+        });
+    }
+
+    let bond_denom = denom_to_string(&hub_config.local_denom);
+    let bond = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom.eq(&bond_denom))
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+    if bond < hub_config.arbitrator_bond {
+        return Err(ContractError::InsufficientArbitratorBond {
+            required_amount: hub_config.arbitrator_bond,
+            sent_amount: bond,
+        });
+    }
+
+    let application = ArbitratorApplication {
+        applicant: info.sender.clone(),
+        fiats,
+        languages,
+        encryption_key,
+        bond,
+        applied_at: env.block.time.seconds(),
+    };
+    ARBITRATOR_APPLICATIONS.save(deps.storage, &info.sender, &application)?;
+
+    let fiats: Vec<String> = application.fiats.iter().map(|f| f.to_string()).collect();
+    let res = Response::new()
+        .add_attribute("action", "apply_arbitrator")
+        .add_attribute("applicant", info.sender.to_string())
+        .add_attribute("fiats", fiats.join(","))
+        .add_attribute("bond", bond.to_string());
+    Ok(res)
+}
+
+/// Approves an application, the applicant becomes an arbitrator of every fiat it applied for.
+fn approve_arbitrator(
+    deps: DepsMut,
+    info: MessageInfo,
+    applicant: Addr,
+) -> Result<Response, ContractError> {
+    let admin = get_hub_admin(deps.as_ref()).addr;
+    let hub_config = get_hub_config(deps.as_ref());
+    assert_ownership(info.sender, admin)?;
+
+    let application = ARBITRATOR_APPLICATIONS
+        .may_load(deps.storage, &applicant)?
+        .ok_or_else(|| ContractError::ArbitratorApplicationNotFound {
+            applicant: applicant.clone(),
+        })?;
+    ARBITRATOR_APPLICATIONS.remove(deps.storage, &applicant);
+
+    for fiat in application.fiats.iter() {
+        ArbitratorModel::create_arbitrator(
+            deps.storage,
+            Arbitrator {
+                arbitrator: applicant.clone(),
+                fiat: fiat.clone(),
+                weight: None,
+                languages: application.languages.clone(),
+            },
+        );
+    }
+
+    // Approving a new application of a bonded arbitrator adds to its bond
+    let mut bond = ARBITRATOR_BONDS
+        .may_load(deps.storage, &applicant)?
+        .unwrap_or(ArbitratorBond {
+            arbitrator: applicant.clone(),
+            amount: Uint128::zero(),
+            slashed_amount: Uint128::zero(),
+            unbonding_since: None,
+        });
+    bond.amount += application.bond;
+    bond.unbonding_since = None;
+    ARBITRATOR_BONDS.save(deps.storage, &applicant, &bond)?;
+
+    let create_profile_sub_msg = update_profile_contact_msg(
+        hub_config.profile_addr.to_string(),
+        applicant.clone(),
+        "N/A".to_string(),
+        application.encryption_key.clone(),
+    );
+
+    let fiats: Vec<String> = application.fiats.iter().map(|f| f.to_string()).collect();
+    let res = Response::new()
+        .add_submessage(create_profile_sub_msg)
+        .add_attribute("action", "approve_arbitrator")
+        .add_attribute("arbitrator", applicant.to_string())
+        .add_attribute("fiats", fiats.join(","))
+        .add_attribute("bond", bond.amount.to_string());
+    Ok(res)
+}
+
+/// Rejects an application and refunds its bond.
+fn reject_arbitrator(
+    deps: DepsMut,
+    info: MessageInfo,
+    applicant: Addr,
+) -> Result<Response, ContractError> {
+    let admin = get_hub_admin(deps.as_ref()).addr;
+    let hub_config = get_hub_config(deps.as_ref());
+    assert_ownership(info.sender, admin)?;
+
+    let application = ARBITRATOR_APPLICATIONS
+        .may_load(deps.storage, &applicant)?
+        .ok_or_else(|| ContractError::ArbitratorApplicationNotFound {
+            applicant: applicant.clone(),
+        })?;
+    ARBITRATOR_APPLICATIONS.remove(deps.storage, &applicant);
+
+    let mut res = Response::new()
+        .add_attribute("action", "reject_arbitrator")
+        .add_attribute("applicant", applicant.to_string());
+    if !application.bond.is_zero() {
+        let bond_denom = denom_to_string(&hub_config.local_denom);
+        let refund = vec![Coin::new(application.bond.u128(), bond_denom)];
+        res = res.add_message(create_send_msg(applicant, refund));
+    }
+    Ok(res)
+}
+
+/// Stops the sender from being drawn for new disputes, its bond can be claimed once the
+/// unbonding period has passed since it started unbonding and since its last open dispute.
+fn unbond_arbitrator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut bond = ARBITRATOR_BONDS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| ContractError::ArbitratorBondNotFound {
+            arbitrator: info.sender.clone(),
+        })?;
+
+    ArbitratorModel::remove_arbitrator(deps.storage, &info.sender)?;
+    bond.unbonding_since = Some(env.block.time.seconds());
+    ARBITRATOR_BONDS.save(deps.storage, &info.sender, &bond)?;
+
+    let res = Response::new()
+        .add_attribute("action", "unbond_arbitrator")
+        .add_attribute("arbitrator", info.sender.to_string())
+        .add_attribute("amount", bond.amount.to_string());
+    Ok(res)
+}
+
+fn claim_arbitrator_bond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
+    let bond = ARBITRATOR_BONDS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| ContractError::ArbitratorBondNotFound {
+            arbitrator: info.sender.clone(),
+        })?;
+    let unbonding_since = bond.unbonding_since.ok_or_else(|| InvalidParameter {
+        parameter: "unbonding_since".to_string(),
+        message: Some("The arbitrator must unbond first.".to_string()),
+    })?;
+
+    let open_disputes = ArbitratorModel::open_disputes(deps.storage, &info.sender);
+    if open_disputes > 0 {
+        return Err(ContractError::ArbitratorHasOpenDisputes { open_disputes });
+    }
+    let last_dispute_closed_at = ArbitratorModel::stats(deps.storage, &info.sender)
+        .last_dispute_closed_at;
+    let unlocks_at =
+        unbonding_since.max(last_dispute_closed_at) + hub_config.arbitrator_unbonding_period;
+    if env.block.time.seconds() < unlocks_at {
+        return Err(ContractError::ArbitratorBondLocked { unlocks_at });
+    }
+
+    ARBITRATOR_BONDS.remove(deps.storage, &info.sender);
+
+    let mut res = Response::new()
+        .add_attribute("action", "claim_arbitrator_bond")
+        .add_attribute("arbitrator", info.sender.to_string())
+        .add_attribute("amount", bond.amount.to_string());
+    if !bond.amount.is_zero() {
+        let bond_denom = denom_to_string(&hub_config.local_denom);
+        let amount = vec![Coin::new(bond.amount.u128(), bond_denom)];
+        res = res.add_message(create_send_msg(info.sender, amount));
+    }
+    Ok(res)
+}
+
+/// Slashes the bond of an arbitrator to compensate the recipient, only callable by the admin.
+fn slash_arbitrator(
+    deps: DepsMut,
+    info: MessageInfo,
+    arbitrator: Addr,
+    amount: Uint128,
+    recipient: Addr,
+) -> Result<Response, ContractError> {
+    let admin = get_hub_admin(deps.as_ref()).addr;
+    let hub_config = get_hub_config(deps.as_ref());
+    assert_ownership(info.sender, admin)?;
+
+    let send_msg = slash_arbitrator_bond(
+        deps.storage,
+        &hub_config,
+        &arbitrator,
+        amount,
+        recipient.clone(),
+    )?;
+
+    let res = Response::new()
+        .add_message(send_msg)
+        .add_attribute("action", "slash_arbitrator")
+        .add_attribute("arbitrator", arbitrator.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("recipient", recipient.to_string());
+    Ok(res)
+}
+
+/// Opens a dispute once the seller's release window is over. The arbitrator isn't known yet,
+/// the dispute stays pending until someone submits the beacon round with `DrawArbitrator`.
+fn dispute_escrow(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade_id: u64,
+    buyer_contact: Option<String>,
+    seller_contact: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut trade = TradeModel::from_store(deps.storage, trade_id);
+    // TODO: check escrow funding timer*
+    // Only the buyer or seller can start a dispute
+    assert_sender_is_buyer_or_seller(
+        info.sender.clone(),
+        trade.buyer.clone(),
+        trade.seller.clone(),
+    )?;
+
+    // Users can only start a dispute once the buyer has clicked `mark paid` after the fiat has been deposited
+    assert_trade_state_change_is_valid(
+        trade.get_state(),
+        TradeState::FiatDeposited,
+        TradeState::EscrowDisputed,
+    )?;
+
+    // The `enables_dispute_at` is defined in the fiat_deposited
+    let enables_dispute_at = trade.enables_dispute_at.ok_or_else(|| InvalidTradeState {
+        current: trade.get_state(),
+        expected: TradeState::FiatDeposited,
+    })?;
+    let current_block_time = env.block.time.seconds();
+    // Returns an error if is too early to open a dispute
+    if enables_dispute_at > current_block_time {
+        let time_to_dispute = enables_dispute_at - current_block_time;
+        return Err(ContractError::PrematureDisputeRequest { time_to_dispute });
+    }
+
+    // Update trade State to TradeState::Disputed, the arbitrator is drawn from a later beacon round
+    trade.set_state(TradeState::EscrowDisputed, &env, &info);
+    let hub_config = get_hub_config(deps.as_ref());
+    trade.arbitrator_buyer_contact = buyer_contact;
+    trade.arbitrator_seller_contact = seller_contact;
+    snapshot_dispute_candidates(deps.storage, &mut trade)?;
+    // The arbitrator is drawn from a beacon round published after the dispute was opened,
+    // so neither party nor the block proposer can know it in advance
+    let beacon = randomness_beacon(&hub_config)?;
+    trade.arbitrator_round = Some(beacon.round_after(current_block_time));
+    // Contacts sent before the arbitrator is drawn have no key version and must be re-sent
+    let key_version = trade
+        .arbitrator
+        .as_ref()
+        .map(|arbitrator| ArbitratorModel::key_version(deps.storage, arbitrator));
+    trade.arbitrator_buyer_contact_key_version =
+        trade.arbitrator_buyer_contact.as_ref().and(key_version);
+    trade.arbitrator_seller_contact_key_version =
+        trade.arbitrator_seller_contact.as_ref().and(key_version);
+    TradeModel::store(deps.storage, &trade)?;
+
+    let offer_stats_msg = update_offer_trade_stats_msg(
+        trade.offer_contract.to_string(),
+        trade.offer_id,
+        TradeState::EscrowDisputed,
+        trade.amount,
+        None,
+    );
+
+    let res = Response::new()
+        .add_submessage(offer_stats_msg)
+        .add_attribute("action", "dispute_escrow")
+        .add_attribute("trade_id", trade.id.to_string())
+        .add_attribute("state", trade.get_state().to_string())
+        .add_attribute("arbitrator", display_arbitrator(&trade))
+        .add_attribute("arbitrator_round", trade.arbitrator_round.unwrap_or(0).to_string());
+
+    Ok(res)
+}
+
+/// Moves a dispute to another arbitrator of the same fiat once the dispute deadline has passed.
+fn reassign_arbitrator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade_id: u64,
+) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
+    let mut trade = TradeModel::from_store(deps.storage, trade_id);
+    // Only the buyer or seller can ask for a new arbitrator
+    assert_sender_is_buyer_or_seller(
+        info.sender.clone(),
+        trade.buyer.clone(),
+        trade.seller.clone(),
+    )?;
+
+    if TradeState::EscrowDisputed.ne(&trade.get_state()) {
+        return Err(InvalidTradeState {
+            current: trade.get_state(),
+            expected: TradeState::EscrowDisputed,
+        });
+    }
+
+    // Disputes opened without a resolution timer have no deadline
+    let block_time = env.block.time.seconds();
+    let dispute_deadline = trade.dispute_deadline.unwrap_or(u64::MAX);
+    if block_time <= dispute_deadline {
+        return Err(ContractError::DisputeDeadlineNotReached { dispute_deadline });
+    }
+
+    // Replacements are drawn from the next beacon round as well
+    if trade.arbitrator_round.is_some() {
+        return Err(ContractError::ArbitratorNotDrawn { trade_id });
+    }
+
+    // Panel members that already voted keep their seat
+    let missed: Vec<Addr> = if trade.panel.is_empty() {
+        trade.arbitrator.clone().into_iter().collect()
+    } else {
+        trade
+            .panel
+            .iter()
+            .filter(|member| !trade.votes.iter().any(|vote| vote.arbitrator.eq(member)))
+            .cloned()
+            .collect()
+    };
+
+    // Every missed seat needs a replacement, otherwise the dispute would wait on a draw that
+    // can't succeed and the escrow would stay locked
+    if trade.dispute_candidates.is_empty() {
+        snapshot_dispute_candidates(deps.storage, &mut trade)?;
+    }
+    let mut excluded = trade.dispute_arbitrators();
+    for _ in &missed {
+        let candidate = ArbitratorModel::pick_candidate(&trade.dispute_candidates, 0, &excluded)
+            .ok_or_else(|| ContractError::NoArbitratorAvailable {
+                fiat: trade.fiat.clone(),
+            })?;
+        excluded.push(candidate);
+    }
+
+    // Records the missed deadline on the previous arbitrators' profiles
+    let sub_msgs: Vec<SubMsg> = missed
+        .iter()
+        .map(|previous_arbitrator| {
+            update_profile_trades_count_msg(
+                hub_config.profile_addr.to_string(),
+                previous_arbitrator.clone(),
+                TradeState::ArbitratorReassigned,
+            )
+        })
+        .collect();
+    trade.pending_replacements = missed;
+
+    let beacon = randomness_beacon(&hub_config)?;
+    trade.arbitrator_round = Some(beacon.round_after(block_time));
+    trade.state_history.push(TradeStateItem {
+        actor: info.sender.clone(),
+        state: TradeState::ArbitratorReassigned,
+        timestamp: block_time,
+    });
+    TradeModel::store(deps.storage, &trade)?;
+
+    let res = Response::new()
+        .add_submessages(sub_msgs)
+        .add_attribute("action", "reassign_arbitrator")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("arbitrator", display_arbitrator(&trade))
+        .add_attribute("arbitrator_round", trade.arbitrator_round.unwrap_or(0).to_string());
+    Ok(res)
+}
+
+/// Draws the arbitrators of a dispute once the beacon round is published, anyone can submit it.
+fn draw_arbitrator(
+    mut deps: DepsMut,
+    env: Env,
+    trade_id: u64,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
+    let mut trade = TradeModel::from_store(deps.storage, trade_id);
+
+    if TradeState::EscrowDisputed.ne(&trade.get_state()) {
+        return Err(InvalidTradeState {
+            current: trade.get_state(),
+            expected: TradeState::EscrowDisputed,
+        });
+    }
+    let round = trade
+        .arbitrator_round
+        .ok_or(ContractError::ArbitratorAlreadyDrawn { trade_id })?;
+
+    // Disputes waiting on a beacon that was since removed from the hub wait for a new one
+    let beacon = randomness_beacon(&hub_config)?;
+    if !beacon.verify(deps.api, round, &signature).unwrap_or(false) {
+        return Err(ContractError::InvalidBeaconSignature { round });
+    }
+    let seed = randomness_seed(&signature);
+    let reassigned =
+        draw_dispute_arbitrators(deps.branch(), &mut trade, &hub_config, &seed, &env)?;
+    TradeModel::store(deps.storage, &trade)?;
+
+    let panel: Vec<String> = trade.panel.iter().map(|member| member.to_string()).collect();
+    let res = Response::new()
+        .add_attribute("action", "draw_arbitrator")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("round", round.to_string())
+        .add_attribute("arbitrator", display_arbitrator(&trade))
+        .add_attribute("panel", panel.join(","))
+        .add_attribute("reassigned", reassigned.join(","));
+    Ok(res)
+}
+
+/// Updates the contact the buyer or the seller shares with the drawn arbitrator.
+fn update_arbitrator_contact(
+    deps: DepsMut,
+    info: MessageInfo,
+    trade_id: u64,
+    contact: String,
+) -> Result<Response, ContractError> {
+    let mut trade = TradeModel::from_store(deps.storage, trade_id);
+    assert_sender_is_buyer_or_seller(
+        info.sender.clone(),
+        trade.buyer.clone(),
+        trade.seller.clone(),
+    )?;
+
+    if TradeState::EscrowDisputed.ne(&trade.get_state()) {
+        return Err(InvalidTradeState {
+            current: trade.get_state(),
+            expected: TradeState::EscrowDisputed,
+        });
+    }
+
+    // The contact is encrypted with the current key of the drawn arbitrator
+    let arbitrator = drawn_arbitrator(&trade)?;
+    let key_version = ArbitratorModel::key_version(deps.storage, &arbitrator);
+    if info.sender.eq(&trade.buyer) {
+        trade.arbitrator_buyer_contact = Some(contact);
+        trade.arbitrator_buyer_contact_key_version = Some(key_version);
+    } else {
+        trade.arbitrator_seller_contact = Some(contact);
+        trade.arbitrator_seller_contact_key_version = Some(key_version);
+    }
+    TradeModel::store(deps.storage, &trade)?;
+
+    let res = Response::new()
+        .add_attribute("action", "update_arbitrator_contact")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("key_version", key_version.to_string());
+    Ok(res)
+}
+
+/// Registers a verifier of fiat payments, only the hub admin can do it.
+fn create_attestor(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    attestor: Addr,
+    public_key: Binary,
+    description: String,
+) -> Result<Response, ContractError> {
+    let admin = get_hub_admin(deps.as_ref());
+    assert_ownership(info.sender, admin.addr)?;
+
+    // Compressed or uncompressed secp256k1 keys
+    if public_key.len() != 33 && public_key.len() != 65 {
+        return Err(InvalidParameter {
+            parameter: "public_key".to_string(),
+            message: Some("Must be a secp256k1 public key.".to_string()),
+        });
+    }
+    assert_text_length_valid("description", &description, ATTESTOR_DESCRIPTION_LIMIT)?;
+
+    let attestors_count = ATTESTORS.keys(deps.storage, None, None, Order::Ascending).count();
+    if !ATTESTORS.has(deps.storage, &attestor) && attestors_count >= MAX_ATTESTORS {
+        return Err(ContractError::AttestorsLimitReached {
+            limit: MAX_ATTESTORS,
+        });
+    }
+
+    ATTESTORS.save(
+        deps.storage,
+        &attestor,
+        &Attestor {
+            attestor: attestor.clone(),
+            public_key,
+            description,
+            registered_at: env.block.time.seconds(),
+        },
+    )?;
+
+    let res = Response::new()
+        .add_attribute("action", "create_attestor")
+        .add_attribute("attestor", attestor.to_string());
+    Ok(res)
+}
+
+pub fn delete_attestor(
+    deps: DepsMut,
+    info: MessageInfo,
+    attestor: Addr,
+) -> Result<Response, ContractError> {
+    let admin = get_hub_admin(deps.as_ref());
+    assert_ownership(info.sender, admin.addr)?;
+
+    ATTESTORS.remove(deps.storage, &attestor);
+
+    let res = Response::new()
+        .add_attribute("action", "delete_attestor")
+        .add_attribute("attestor", attestor.to_string());
+    Ok(res)
+}
+
+/// Replaces the arbitrators the sender doesn't want deciding their disputes.
+fn set_arbitrator_conflicts(
+    deps: DepsMut,
+    info: MessageInfo,
+    mut arbitrators: Vec<Addr>,
+) -> Result<Response, ContractError> {
+    arbitrators.sort();
+    arbitrators.dedup();
+    if arbitrators.len() > MAX_ARBITRATOR_CONFLICTS {
+        return Err(InvalidParameter {
+            parameter: "arbitrators".to_string(),
+            message: Some(format!("Up to {MAX_ARBITRATOR_CONFLICTS} conflicts.")),
+        });
+    }
+    ARBITRATOR_CONFLICTS.save(deps.storage, &info.sender, &arbitrators)?;
+
+    let conflicts: Vec<String> = arbitrators.iter().map(|a| a.to_string()).collect();
+    let res = Response::new()
+        .add_attribute("action", "set_arbitrator_conflicts")
+        .add_attribute("user", info.sender.to_string())
+        .add_attribute("arbitrators", conflicts.join(","));
+    Ok(res)
+}
+
+fn settle_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade_id: u64,
+    winner: Addr,
+    decision_hash: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut trade = TradeModel::from_store(deps.storage, trade_id);
+
+    // Check if caller is the arbitrator of the given trade
+    let arbitrator = drawn_arbitrator(&trade)?;
+    if arbitrator.ne(&info.sender) {
+        return Err(ContractError::Unauthorized {
+            owner: arbitrator,
+            caller: info.sender,
+        });
+    }
+
+    // Check if TradeState is EscrowDisputed
+    if TradeState::EscrowDisputed.ne(&trade.get_state()) {
+        return Err(InvalidTradeState {
+            current: trade.get_state(),
+            expected: TradeState::EscrowDisputed,
+        });
+    }
+
+    // Panel disputes are settled through VoteDispute
+    if !trade.panel.is_empty() {
+        return Err(ContractError::DisputeHasPanel { trade_id });
+    }
+
+    if let Some(decision_hash) = &decision_hash {
+        assert_hash_valid("decision_hash", decision_hash)?;
+    }
+    trade.decision_hash = decision_hash;
+
+    let arbitrators = vec![arbitrator];
+    settle_for_winner(deps, env, info, trade, winner, arbitrators)
+}
+
+/// Adds evidence of the buyer or the seller to the dispute log. Evidence can be submitted
+/// until the dispute is settled, including while a decision can be appealed.
+fn submit_evidence(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade_id: u64,
+    evidence_hash: String,
+    uri: Option<String>,
+) -> Result<Response, ContractError> {
+    let trade = TradeModel::from_store(deps.storage, trade_id);
+    assert_sender_is_buyer_or_seller(
+        info.sender.clone(),
+        trade.buyer.clone(),
+        trade.seller.clone(),
+    )?;
+
+    let state = trade.get_state();
+    if TradeState::EscrowDisputed.ne(&state) && TradeState::DisputeDecided.ne(&state) {
+        return Err(InvalidTradeState {
+            current: state,
+            expected: TradeState::EscrowDisputed,
+        });
+    }
+
+    assert_hash_valid("evidence_hash", &evidence_hash)?;
+    if let Some(uri) = &uri {
+        assert_text_length_valid("uri", uri, EVIDENCE_URI_LIMIT)?;
+    }
+
+    let evidence = append_dispute_evidence(
+        deps.storage,
+        DisputeEvidence {
+            id: 0,
+            trade_id,
+            kind: DisputeEvidenceKind::Submitted,
+            author: info.sender.clone(),
+            evidence_hash: Some(evidence_hash.clone()),
+            uri,
+            party: None,
+            description: None,
+            timestamp: env.block.time.seconds(),
+        },
+    )?;
+
+    let res = Response::new()
+        .add_attribute("action", "submit_evidence")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("evidence_id", evidence.id.to_string())
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("evidence_hash", evidence_hash);
+    Ok(res)
+}
+
+/// Lets an arbitrator deciding the dispute ask the buyer or the seller for evidence.
+fn request_evidence(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade_id: u64,
+    party: Addr,
+    description: String,
+) -> Result<Response, ContractError> {
+    let trade = TradeModel::from_store(deps.storage, trade_id);
+    if TradeState::EscrowDisputed.ne(&trade.get_state()) {
+        return Err(InvalidTradeState {
+            current: trade.get_state(),
+            expected: TradeState::EscrowDisputed,
+        });
+    }
+
+    let arbitrator = drawn_arbitrator(&trade)?;
+    if !trade.dispute_arbitrators().contains(&info.sender) {
+        return Err(ContractError::Unauthorized {
+            owner: arbitrator,
+            caller: info.sender,
+        });
+    }
+
+    if party.ne(&trade.buyer) && party.ne(&trade.seller) {
+        return Err(ContractError::InvalidSender {
+            sender: party,
+            buyer: trade.buyer,
+            seller: trade.seller,
+        });
+    }
+    assert_text_length_valid("description", &description, EVIDENCE_DESCRIPTION_LIMIT)?;
+
+    let evidence = append_dispute_evidence(
+        deps.storage,
+        DisputeEvidence {
+            id: 0,
+            trade_id,
+            kind: DisputeEvidenceKind::Requested,
+            author: info.sender.clone(),
+            evidence_hash: None,
+            uri: None,
+            party: Some(party.clone()),
+            description: Some(description),
+            timestamp: env.block.time.seconds(),
+        },
+    )?;
+
+    let res = Response::new()
+        .add_attribute("action", "request_evidence")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("evidence_id", evidence.id.to_string())
+        .add_attribute("arbitrator", info.sender.to_string())
+        .add_attribute("party", party.to_string());
+    Ok(res)
+}
+
+// Each party and arbitrator has its own share of the evidence log, so one of them filling it
+// can't keep the others from adding evidence or requests
+fn append_dispute_evidence(
+    storage: &mut dyn Storage,
+    evidence: DisputeEvidence,
+) -> Result<DisputeEvidence, ContractError> {
+    let author_count =
+        DisputeEvidenceModel::author_count(storage, evidence.trade_id, &evidence.author);
+    if author_count >= MAX_DISPUTE_EVIDENCE_ITEMS {
+        return Err(ContractError::DisputeEvidenceLimitReached {
+            limit: MAX_DISPUTE_EVIDENCE_ITEMS,
+        });
+    }
+    Ok(DisputeEvidenceModel::append(storage, evidence)?)
+}
+
+/// Records the vote of a panel member, the dispute is settled once a majority agrees.
+fn vote_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade_id: u64,
+    winner: Addr,
+    decision_hash: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut trade = TradeModel::from_store(deps.storage, trade_id);
+
+    // Only panel members can vote
+    let arbitrator = drawn_arbitrator(&trade)?;
+    if !trade.panel.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {
+            owner: arbitrator,
+            caller: info.sender,
+        });
+    }
+
+    if TradeState::EscrowDisputed.ne(&trade.get_state()) {
+        return Err(InvalidTradeState {
+            current: trade.get_state(),
+            expected: TradeState::EscrowDisputed,
+        });
+    }
+
+    if trade.votes.iter().any(|vote| vote.arbitrator.eq(&info.sender)) {
+        return Err(ContractError::DisputeAlreadyVoted {
+            arbitrator: info.sender,
+        });
+    }
+
+    if winner.ne(&trade.buyer) && winner.ne(&trade.seller) {
+        return Err(ContractError::InvalidSender {
+            sender: winner,
+            buyer: trade.buyer,
+            seller: trade.seller,
+        });
+    }
+
+    if let Some(decision_hash) = &decision_hash {
+        assert_hash_valid("decision_hash", decision_hash)?;
+    }
+
+    trade.votes.push(DisputeVote {
+        arbitrator: info.sender.clone(),
+        winner: winner.clone(),
+        decision_hash,
+    });
+
+    let majority_voters: Vec<Addr> = trade
+        .votes
+        .iter()
+        .filter(|vote| vote.winner.eq(&winner))
+        .map(|vote| vote.arbitrator.clone())
+        .collect();
+    if majority_voters.len() > trade.panel.len() / 2 {
+        return settle_for_winner(deps, env, info, trade, winner, majority_voters);
+    }
+
+    TradeModel::store(deps.storage, &trade)?;
+
+    let res = Response::new()
+        .add_attribute("action", "vote_dispute")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("arbitrator", info.sender.to_string())
+        .add_attribute("winner", winner.to_string())
+        .add_attribute("votes", trade.votes.len().to_string());
+    Ok(res)
+}
+
+/// Decides a dispute for the winner, which must be the buyer or the seller.
+fn settle_for_winner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade: Trade,
+    winner: Addr,
+    arbitrators: Vec<Addr>,
+) -> Result<Response, ContractError> {
+    if winner.ne(&trade.buyer) && winner.ne(&trade.seller) {
+        return Err(ContractError::InvalidSender {
+            sender: winner,
+            buyer: trade.buyer,
+            seller: trade.seller,
+        });
+    }
+    let outcome = DisputeOutcome::Winner { winner };
+    decide_dispute(deps, env, info, trade, outcome, arbitrators)
+}
+
+/// Decides a dispute. High value decisions wait for the appeal window to close, others are
+/// released right away.
+fn decide_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut trade: Trade,
+    outcome: DisputeOutcome,
+    arbitrators: Vec<Addr>,
+) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
+
+    // A decision taken on appeal is final
+    let threshold = hub_config.appeal_threshold;
+    let appealable = hub_config.appeal_window > 0
+        && !threshold.is_zero()
+        && trade.usd_amount >= threshold
+        && trade.appeal.is_none();
+    if !appealable {
+        return release_outcome(deps, env, info, trade, outcome, arbitrators);
+    }
+
+    let decided_at = env.block.time.seconds();
+    let appeal_deadline = decided_at + hub_config.appeal_window;
+    trade.decision = Some(DisputeDecision {
+        outcome: outcome.clone(),
+        arbitrators: arbitrators.clone(),
+        decided_at,
+        appeal_deadline,
+    });
+    trade.set_state(TradeState::DisputeDecided, &env, &info);
+    TradeModel::store(deps.storage, &trade)?;
 
+    let arbitrators: Vec<String> = arbitrators.iter().map(|a| a.to_string()).collect();
+    let res = Response::new()
+        .add_attribute("action", "decide_dispute")
+        .add_attribute("trade_id", trade.id.to_string())
+        .add_attribute("arbitrator", arbitrators.join(","))
+        .add_attribute("state", trade.get_state().to_string())
+        .add_attribute("appeal_deadline", appeal_deadline.to_string());
+    let res = match outcome {
+        DisputeOutcome::Winner { winner } => res.add_attribute("winner", winner.to_string()),
+        DisputeOutcome::Split { buyer_share } => {
+            res.add_attribute("buyer_share", buyer_share.to_string())
+        }
+    };
     Ok(res)
 }
 
-pub fn delete_arbitrator(
+fn release_outcome(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    arbitrator: Addr,
-    fiat: FiatCurrency,
+    trade: Trade,
+    outcome: DisputeOutcome,
+    arbitrators: Vec<Addr>,
 ) -> Result<Response, ContractError> {
-    let admin = get_hub_admin(deps.as_ref());
-    assert_ownership(info.sender, admin.addr)?;
-
-    let index = arbitrator.clone().to_string() + &fiat.to_string();
-
-    arbitrators().remove(deps.storage, &index).unwrap();
-
-    let res = Response::new()
-        .add_attribute("action", "delete_arbitrator")
-        .add_attribute("arbitrator", arbitrator.to_string())
-        .add_attribute("asset", fiat.to_string());
-
-    Ok(res)
+    match outcome {
+        DisputeOutcome::Winner { winner } => {
+            release_settlement(deps, env, info, trade, winner, arbitrators)
+        }
+        DisputeOutcome::Split { buyer_share } => {
+            release_split(deps, env, info, trade, buyer_share, arbitrators)
+        }
+    }
 }
 
-fn dispute_escrow(
+/// Lets the losing party appeal a decision by posting the appeal bond in the trade denom.
+/// The dispute goes back to EscrowDisputed and is reviewed by newly drawn arbitrators.
+fn appeal_dispute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     trade_id: u64,
-    buyer_contact: String,
-    seller_contact: String,
 ) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
     let mut trade = TradeModel::from_store(deps.storage, trade_id);
-    // TODO: check escrow funding timer*
-    // Only the buyer or seller can start a dispute
-    assert_sender_is_buyer_or_seller(
-        info.sender.clone(),
-        trade.buyer.clone(),
-        trade.seller.clone(),
-    )
-    .unwrap();
+    let decision = match (&trade.decision, trade.get_state()) {
+        (Some(decision), TradeState::DisputeDecided) => decision.clone(),
+        _ => {
+            return Err(InvalidTradeState {
+                current: trade.get_state(),
+                expected: TradeState::DisputeDecided,
+            })
+        }
+    };
 
-    // Users can only start a dispute once the buyer has clicked `mark paid` after the fiat has been deposited
-    assert_trade_state_change_is_valid(
-        trade.get_state(),
-        TradeState::FiatDeposited,
-        TradeState::EscrowDisputed,
-    )
-    .unwrap();
+    // Either party can appeal a split, only the loser a decision for the other party
+    let appellants = match &decision.outcome {
+        DisputeOutcome::Winner { winner } if winner.eq(&trade.buyer) => vec![trade.seller.clone()],
+        DisputeOutcome::Winner { .. } => vec![trade.buyer.clone()],
+        DisputeOutcome::Split { .. } => vec![trade.buyer.clone(), trade.seller.clone()],
+    };
+    assert_multiple_ownership(info.sender.clone(), appellants)?;
 
-    // The `enables_dispute_at` is defined in the fiat_deposited
-    let enables_dispute_at = trade.enables_dispute_at.unwrap();
-    let current_block_time = env.block.time.seconds();
-    // Returns an error if is too early to open a dispute
-    if enables_dispute_at > current_block_time {
-        let time_to_dispute = enables_dispute_at - current_block_time;
-        return Err(ContractError::PrematureDisputeRequest { time_to_dispute });
+    let block_time = env.block.time.seconds();
+    if block_time > decision.appeal_deadline {
+        return Err(ContractError::AppealWindowClosed {
+            appeal_deadline: decision.appeal_deadline,
+        });
+    }
+
+    let one_e18 = Decimal::one().atomics();
+    let required_bond = trade
+        .amount
+        .multiply_ratio(hub_config.appeal_bond_pct.atomics(), one_e18);
+    let trade_denom = denom_to_string(&trade.denom);
+    let bond = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom.eq(&trade_denom))
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+    if bond < required_bond {
+        return Err(ContractError::InsufficientAppealBond {
+            required_amount: required_bond,
+            sent_amount: bond,
+        });
     }
 
-    // Update trade State to TradeState::Disputed and sets arbitrator
+    // The arbitrators that decided stay accountable until the appeal is settled,
+    // the rest of their panel is done with the dispute
+    let outvoted: Vec<Addr> = trade
+        .dispute_arbitrators()
+        .into_iter()
+        .filter(|arbitrator| !decision.arbitrators.contains(arbitrator))
+        .collect();
+    ArbitratorModel::update_open_disputes(deps.storage, &outvoted, false)?;
+
+    trade.appeal = Some(DisputeAppeal {
+        appellant: info.sender.clone(),
+        bond,
+        appealed_at: block_time,
+    });
+    trade.arbitrator = None;
+    trade.panel = vec![];
+    trade.votes = vec![];
+    trade.pending_replacements = vec![];
+    trade.dispute_deadline = None;
+    trade.arbitrator_buyer_contact = None;
+    trade.arbitrator_buyer_contact_key_version = None;
+    trade.arbitrator_seller_contact = None;
+    trade.arbitrator_seller_contact_key_version = None;
+    trade.state_history.push(TradeStateItem {
+        actor: info.sender.clone(),
+        state: TradeState::DisputeAppealed,
+        timestamp: block_time,
+    });
     trade.set_state(TradeState::EscrowDisputed, &env, &info);
-    trade.arbitrator_buyer_contact = Some(buyer_contact);
-    trade.arbitrator_seller_contact = Some(seller_contact);
-    TradeModel::store(deps.storage, &trade).unwrap();
+    snapshot_dispute_candidates(deps.storage, &mut trade)?;
+    let beacon = randomness_beacon(&hub_config)?;
+    trade.arbitrator_round = Some(beacon.round_after(block_time));
+    TradeModel::store(deps.storage, &trade)?;
 
     let res = Response::new()
-        .add_attribute("action", "dispute_escrow")
-        .add_attribute("trade_id", trade.id.to_string())
-        .add_attribute("state", trade.get_state().to_string())
-        .add_attribute("arbitrator", trade.arbitrator.to_string());
-
+        .add_attribute("action", "appeal_dispute")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("appellant", info.sender.to_string())
+        .add_attribute("bond", bond.to_string())
+        .add_attribute("arbitrator", display_arbitrator(&trade))
+        .add_attribute("arbitrator_round", trade.arbitrator_round.unwrap_or(0).to_string());
     Ok(res)
 }
 
-fn settle_dispute(
+/// Releases a decision once its appeal window closed without an appeal, anyone can call it.
+fn finalize_settlement(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     trade_id: u64,
-    winner: Addr,
 ) -> Result<Response, ContractError> {
-    let hub_config = get_hub_config(deps.as_ref());
-    let mut trade = TradeModel::from_store(deps.storage, trade_id);
+    let trade = TradeModel::from_store(deps.storage, trade_id);
+    let decision = match (&trade.decision, trade.get_state()) {
+        (Some(decision), TradeState::DisputeDecided) => decision.clone(),
+        _ => {
+            return Err(InvalidTradeState {
+                current: trade.get_state(),
+                expected: TradeState::DisputeDecided,
+            })
+        }
+    };
 
-    // Check if caller is the arbitrator of the given trade
-    if trade.arbitrator.ne(&info.sender) {
-        return Err(ContractError::Unauthorized {
-            owner: trade.arbitrator.clone(),
-            caller: info.sender,
+    if env.block.time.seconds() <= decision.appeal_deadline {
+        return Err(ContractError::AppealWindowOpen {
+            appeal_deadline: decision.appeal_deadline,
         });
     }
 
-    // Check if TradeState is EscrowDisputed
-    if TradeState::EscrowDisputed.ne(&trade.get_state()) {
-        return Err(InvalidTradeState {
-            current: trade.get_state(),
-            expected: TradeState::EscrowDisputed,
-        });
-    }
+    let res = release_outcome(deps, env, info, trade, decision.outcome, decision.arbitrators)?;
+    Ok(res
+        .add_attribute("action", "finalize_settlement")
+        .add_attribute("trade_id", trade_id.to_string()))
+}
+
+/// Pays the escrow to the winner of a dispute and splits the arbitration fee between the
+/// arbitrators that decided it.
+fn release_settlement(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut trade: Trade,
+    winner: Addr,
+    arbitrators: Vec<Addr>,
+) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
 
     // Load Offer
     let offer = load_offer(
@@ -967,6 +2734,21 @@ fn settle_dispute(
         });
     }
     TradeModel::store(deps.storage, &trade).unwrap();
+    ArbitratorModel::update_open_disputes(deps.storage, &trade.dispute_arbitrators(), false)?;
+
+    // Compute arbitration fee as amount * pct, keeping token atomics
+    let one_e18 = Decimal::one().atomics();
+    let arbitration_fee_amount = trade
+        .amount
+        .multiply_ratio(hub_config.arbitration_fee_pct.atomics(), one_e18);
+    let fee_shares = arbitration_fee_shares(&arbitrators, arbitration_fee_amount);
+    record_dispute_resolution(deps.storage, &trade, &fee_shares, env.block.time.seconds())?;
+
+    let outcome = DisputeOutcome::Winner {
+        winner: winner.clone(),
+    };
+    let (mut appeal_msgs, appeal_outcome) =
+        resolve_appeal(deps.storage, &hub_config, &trade, &outcome)?;
 
     // Collect Protocol Fees
     let trade_denom = denom_to_string(&trade.denom);
@@ -980,11 +2762,6 @@ fn settle_dispute(
     );
 
     // Pay arbitration fee
-    // Compute arbitration fee as amount * pct, keeping token atomics
-    let one_e18 = Decimal::one().atomics();
-    let arbitration_fee_amount = trade
-        .amount
-        .multiply_ratio(hub_config.arbitration_fee_pct.atomics(), one_e18);
     let mut release_amount = trade.amount.sub(arbitration_fee_amount);
 
     // Only deducts fees from the release_amount if the maker (offer owner) is the buyer
@@ -992,15 +2769,15 @@ fn settle_dispute(
         release_amount = release_amount.sub(fee_info.total_fees());
     }
 
-    // Send funds to winner and arbitrator
+    // Send funds to winner and arbitrators
     let denom = denom_to_string(&trade.denom);
-    let arbitration_fee = vec![Coin::new(arbitration_fee_amount.u128(), denom.clone())];
     let winner_amount = vec![Coin::new(release_amount.u128(), denom.clone())];
     send_msgs.push(SubMsg::new(create_send_msg(winner.clone(), winner_amount)));
-    send_msgs.push(SubMsg::new(create_send_msg(
-        trade.arbitrator.clone(),
-        arbitration_fee,
-    )));
+    for (arbitrator, amount) in fee_shares {
+        let arbitration_fee = vec![Coin::new(amount.u128(), denom.clone())];
+        send_msgs.push(SubMsg::new(create_send_msg(arbitrator, arbitration_fee)));
+    }
+    send_msgs.append(&mut appeal_msgs);
 
     // Create Update Profile SubMsgs
     let profile_submsgs = create_update_trades_count_msgs(
@@ -1010,11 +2787,152 @@ fn settle_dispute(
         trade.get_state(),
     );
 
+    let arbitrators: Vec<String> = arbitrators.iter().map(|a| a.to_string()).collect();
     let res = Response::new()
-        .add_attribute("arbitrator", trade.arbitrator.to_string())
+        .add_attribute("arbitrator", arbitrators.join(","))
         .add_attribute("winner", winner.to_string())
         .add_attribute("maker", maker.to_string())
         .add_attribute("taker", taker.to_string())
+        .add_attribute("appeal", appeal_outcome)
+        .add_submessages(profile_submsgs)
+        .add_submessages(send_msgs);
+    Ok(res)
+}
+
+/// Decides a dispute by splitting the escrow between the buyer and the seller.
+fn settle_dispute_split(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trade_id: u64,
+    buyer_share: Decimal,
+    decision_hash: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut trade = TradeModel::from_store(deps.storage, trade_id);
+
+    // Check if caller is the arbitrator of the given trade
+    let arbitrator = drawn_arbitrator(&trade)?;
+    if arbitrator.ne(&info.sender) {
+        return Err(ContractError::Unauthorized {
+            owner: arbitrator,
+            caller: info.sender,
+        });
+    }
+
+    // Check if TradeState is EscrowDisputed
+    if TradeState::EscrowDisputed.ne(&trade.get_state()) {
+        return Err(InvalidTradeState {
+            current: trade.get_state(),
+            expected: TradeState::EscrowDisputed,
+        });
+    }
+
+    // Panel disputes are settled through VoteDispute
+    if !trade.panel.is_empty() {
+        return Err(ContractError::DisputeHasPanel { trade_id });
+    }
+
+    if buyer_share > Decimal::one() {
+        return Err(InvalidParameter {
+            parameter: "buyer_share".to_string(),
+            message: Some("Must be between 0 and 1.".to_string()),
+        });
+    }
+
+    if let Some(decision_hash) = &decision_hash {
+        assert_hash_valid("decision_hash", decision_hash)?;
+    }
+    trade.decision_hash = decision_hash;
+
+    let arbitrators = vec![arbitrator];
+    let outcome = DisputeOutcome::Split { buyer_share };
+    decide_dispute(deps, env, info, trade, outcome, arbitrators)
+}
+
+/// Splits the escrow of a dispute between the buyer and the seller.
+/// The arbitration and protocol fees are taken before the split, so both parties bear them
+/// in proportion to their share.
+fn release_split(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut trade: Trade,
+    buyer_share: Decimal,
+    arbitrators: Vec<Addr>,
+) -> Result<Response, ContractError> {
+    let hub_config = get_hub_config(deps.as_ref());
+    let trade_id = trade.id;
+    trade.set_state(TradeState::SettledSplit, &env, &info);
+    TradeModel::store(deps.storage, &trade)?;
+    ArbitratorModel::update_open_disputes(deps.storage, &trade.dispute_arbitrators(), false)?;
+
+    // The escrow holds the amount plus the fees sent by the maker when they funded it
+    let one_e18 = Decimal::one().atomics();
+    let arbitration_fee_amount = trade
+        .amount
+        .multiply_ratio(hub_config.arbitration_fee_pct.atomics(), one_e18);
+    let fee_shares = arbitration_fee_shares(&arbitrators, arbitration_fee_amount);
+    record_dispute_resolution(deps.storage, &trade, &fee_shares, env.block.time.seconds())?;
+
+    let outcome = DisputeOutcome::Split { buyer_share };
+    let (mut appeal_msgs, appeal_outcome) =
+        resolve_appeal(deps.storage, &hub_config, &trade, &outcome)?;
+
+    // Collect Protocol Fees
+    let trade_denom = denom_to_string(&trade.denom);
+    let mut send_msgs: Vec<SubMsg> = vec![];
+    let fee_info = add_protocol_fees_msgs(
+        deps,
+        &mut send_msgs,
+        &trade.amount,
+        trade_denom.clone(),
+        &hub_config,
+    );
+
+    let split_amount = (trade.amount + trade.escrow_fees)
+        .sub(fee_info.total_fees())
+        .sub(arbitration_fee_amount);
+    let buyer_amount = split_amount.multiply_ratio(buyer_share.atomics(), one_e18);
+    let seller_amount = split_amount.sub(buyer_amount);
+
+    let payouts = [
+        (trade.buyer.clone(), buyer_amount),
+        (trade.seller.clone(), seller_amount),
+    ];
+    for (recipient, amount) in payouts.into_iter().chain(fee_shares) {
+        if !amount.is_zero() {
+            send_msgs.push(SubMsg::new(create_send_msg(
+                recipient,
+                vec![Coin::new(amount.u128(), trade_denom.clone())],
+            )));
+        }
+    }
+    send_msgs.append(&mut appeal_msgs);
+    send_msgs.push(update_offer_trade_stats_msg(
+        trade.offer_contract.to_string(),
+        trade.offer_id,
+        TradeState::SettledSplit,
+        trade.amount,
+        None,
+    ));
+
+    // Create Update Profile SubMsgs
+    let profile_submsgs = create_update_trades_count_msgs(
+        hub_config.profile_addr.to_string(),
+        trade.buyer.clone(),
+        trade.seller.clone(),
+        trade.get_state(),
+    );
+
+    let arbitrators: Vec<String> = arbitrators.iter().map(|a| a.to_string()).collect();
+    let res = Response::new()
+        .add_attribute("action", "settle_dispute_split")
+        .add_attribute("trade_id", trade_id.to_string())
+        .add_attribute("arbitrator", arbitrators.join(","))
+        .add_attribute("buyer_share", buyer_share.to_string())
+        .add_attribute("buyer_amount", buyer_amount.to_string())
+        .add_attribute("seller_amount", seller_amount.to_string())
+        .add_attribute("appeal", appeal_outcome)
         .add_submessages(profile_submsgs)
         .add_submessages(send_msgs);
     Ok(res)
@@ -1163,6 +3081,305 @@ fn handle_swap_reply(deps: DepsMut, _msg: Reply) -> Result<Response, ContractErr
     }
 }
 
+// Draws the arbitrator and the panel of a new dispute from a seed, or the replacements of the
+// arbitrators that missed the dispute deadline. Returns the reassigned arbitrators.
+fn draw_dispute_arbitrators(
+    deps: DepsMut,
+    trade: &mut Trade,
+    hub_config: &HubConfig,
+    seed: &[u8; 32],
+    env: &Env,
+) -> Result<Vec<String>, ContractError> {
+    // Disputes opened before the candidates were snapshotted take their snapshot now
+    if trade.dispute_candidates.is_empty() {
+        snapshot_dispute_candidates(deps.storage, trade)?;
+    }
+    let candidates = trade.dispute_candidates.clone();
+    let mut excluded: Vec<Addr> = trade.arbitrator.clone().into_iter().collect();
+    excluded.append(&mut trade.panel.clone());
+
+    let mut reassigned: Vec<String> = vec![];
+    match trade.arbitrator.clone() {
+        None => {
+            let arbitrator =
+                ArbitratorModel::pick_candidate(&candidates, random_value(seed, 0), &excluded)
+                    .ok_or_else(|| ContractError::NoArbitratorAvailable {
+                        fiat: trade.fiat.clone(),
+                    })?;
+            trade.arbitrator = Some(arbitrator.clone());
+            // High value trades are decided by a panel drawn for the fiat
+            let threshold = hub_config.arbitration_panel_threshold;
+            if !threshold.is_zero() && trade.usd_amount >= threshold {
+                excluded.push(arbitrator.clone());
+                trade.panel = draw_arbitration_panel(
+                    &candidates,
+                    arbitrator,
+                    hub_config.arbitration_panel_size,
+                    seed,
+                    &mut excluded,
+                );
+            }
+            let arbitrators = trade.dispute_arbitrators();
+            ArbitratorModel::update_open_disputes(deps.storage, &arbitrators, true)?;
+            ArbitratorModel::update_stats(deps.storage, &arbitrators, |stats| {
+                stats.assigned_disputes += 1
+            })?;
+        }
+        Some(current_arbitrator) => {
+            let pending = std::mem::take(&mut trade.pending_replacements);
+            let mut replaced: Vec<Addr> = vec![];
+            let mut replacements: Vec<Addr> = vec![];
+            for (i, previous_arbitrator) in pending.iter().enumerate() {
+                // Arbitrators without a replacement keep their seat, so the dispute can still
+                // be settled instead of waiting on a draw that can't succeed
+                let arbitrator = match ArbitratorModel::pick_candidate(
+                    &candidates,
+                    random_value(seed, i as u64),
+                    &excluded,
+                ) {
+                    Some(arbitrator) => arbitrator,
+                    None => continue,
+                };
+
+                // The new arbitrator can't read the contacts sent to the previous one
+                if current_arbitrator.eq(previous_arbitrator) {
+                    trade.arbitrator = Some(arbitrator.clone());
+                    trade.arbitrator_buyer_contact = None;
+                    trade.arbitrator_buyer_contact_key_version = None;
+                    trade.arbitrator_seller_contact = None;
+                    trade.arbitrator_seller_contact_key_version = None;
+                }
+                let seat = trade.panel.iter_mut().find(|member| member.eq(&previous_arbitrator));
+                if let Some(seat) = seat {
+                    *seat = arbitrator.clone();
+                }
+                excluded.push(arbitrator.clone());
+                reassigned.push(format!("{previous_arbitrator}:{arbitrator}"));
+                replaced.push(previous_arbitrator.clone());
+                replacements.push(arbitrator);
+            }
+            ArbitratorModel::update_open_disputes(deps.storage, &replaced, false)?;
+            ArbitratorModel::update_open_disputes(deps.storage, &replacements, true)?;
+            let block_time = env.block.time.seconds();
+            ArbitratorModel::update_stats(deps.storage, &replaced, |stats| {
+                stats.reassignments += 1;
+                stats.last_dispute_closed_at = block_time;
+            })?;
+            ArbitratorModel::update_stats(deps.storage, &replacements, |stats| {
+                stats.assigned_disputes += 1
+            })?;
+        }
+    }
+
+    trade.arbitrator_round = None;
+    if hub_config.dispute_resolution_timer > 0 {
+        let block_time = env.block.time.seconds();
+        trade.dispute_deadline = Some(block_time + hub_config.dispute_resolution_timer);
+    }
+    Ok(reassigned)
+}
+
+// Draws the panel of a dispute around its arbitrator, keeping an odd number of members.
+// Returns an empty panel when there aren't enough arbitrators for the fiat.
+fn draw_arbitration_panel(
+    candidates: &[DisputeCandidate],
+    arbitrator: Addr,
+    panel_size: u8,
+    seed: &[u8; 32],
+    excluded: &mut Vec<Addr>,
+) -> Vec<Addr> {
+    let mut panel = vec![arbitrator];
+    for i in 1..panel_size as u64 {
+        match ArbitratorModel::pick_candidate(candidates, random_value(seed, i), excluded) {
+            Some(arbitrator) => {
+                excluded.push(arbitrator.clone());
+                panel.push(arbitrator);
+            }
+            None => break,
+        }
+    }
+    if panel.len().is_multiple_of(2) {
+        panel.pop();
+    }
+    if panel.len() > 1 {
+        panel
+    } else {
+        vec![]
+    }
+}
+
+// Takes up to the given amount from the bond of an arbitrator and sends it to the recipient
+fn slash_arbitrator_bond(
+    storage: &mut dyn Storage,
+    hub_config: &HubConfig,
+    arbitrator: &Addr,
+    amount: Uint128,
+    recipient: Addr,
+) -> Result<CosmosMsg, ContractError> {
+    let mut bond = ARBITRATOR_BONDS
+        .may_load(storage, arbitrator)?
+        .ok_or_else(|| ContractError::ArbitratorBondNotFound {
+            arbitrator: arbitrator.clone(),
+        })?;
+    if amount.is_zero() || amount > bond.amount {
+        return Err(InvalidParameter {
+            parameter: "amount".to_string(),
+            message: Some(format!("Must be between 1 and the bond of {}.", bond.amount)),
+        });
+    }
+    bond.amount -= amount;
+    bond.slashed_amount += amount;
+    ARBITRATOR_BONDS.save(storage, arbitrator, &bond)?;
+
+    let bond_denom = denom_to_string(&hub_config.local_denom);
+    Ok(create_send_msg(recipient, vec![Coin::new(amount.u128(), bond_denom)]))
+}
+
+// Settles the appeal of a dispute, if any. When the new decision upholds the appealed one, the
+// bond goes to the arbitrators that took it. When it reverses it, the bond goes back to the
+// appellant along with part of the bonds of those arbitrators.
+fn resolve_appeal(
+    storage: &mut dyn Storage,
+    hub_config: &HubConfig,
+    trade: &Trade,
+    outcome: &DisputeOutcome,
+) -> Result<(Vec<SubMsg>, &'static str), ContractError> {
+    let (appeal, decision) = match (&trade.appeal, &trade.decision) {
+        (Some(appeal), Some(decision)) => (appeal, decision),
+        _ => return Ok((vec![], "none")),
+    };
+    let upheld = decision.outcome.eq(outcome);
+    ArbitratorModel::update_open_disputes(storage, &decision.arbitrators, false)?;
+    ArbitratorModel::update_stats(storage, &decision.arbitrators, |stats| {
+        stats.appealed_decisions += 1;
+        if !upheld {
+            stats.overturned_decisions += 1;
+        }
+    })?;
+
+    let payouts = if upheld {
+        arbitration_fee_shares(&decision.arbitrators, appeal.bond)
+    } else {
+        vec![(appeal.appellant.clone(), appeal.bond)]
+    };
+    let denom = denom_to_string(&trade.denom);
+    let mut msgs: Vec<SubMsg> = payouts
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(recipient, amount)| {
+            let appeal_bond = vec![Coin::new(amount.u128(), denom.clone())];
+            SubMsg::new(create_send_msg(recipient, appeal_bond))
+        })
+        .collect();
+    if upheld {
+        return Ok((msgs, "upheld"));
+    }
+
+    // Arbitrators registered by the admin without applying have no bond to slash
+    for arbitrator in &decision.arbitrators {
+        let bond = ARBITRATOR_BONDS
+            .may_load(storage, arbitrator)?
+            .map(|bond| bond.amount)
+            .unwrap_or_default();
+        let amount = hub_config.overturned_decision_slash.min(bond);
+        if !amount.is_zero() {
+            let recipient = appeal.appellant.clone();
+            let msg = slash_arbitrator_bond(storage, hub_config, arbitrator, amount, recipient)?;
+            msgs.push(SubMsg::new(msg));
+        }
+    }
+    Ok((msgs, "reversed"))
+}
+
+// Splits the arbitration fee between the arbitrators that decided a dispute, the first one
+// also gets the rounding remainder
+fn arbitration_fee_shares(arbitrators: &[Addr], fee: Uint128) -> Vec<(Addr, Uint128)> {
+    let arbitrators_count = Uint128::from(arbitrators.len() as u128);
+    let arbitrator_share = fee.multiply_ratio(1u128, arbitrators_count);
+    let remainder = fee - arbitrator_share * arbitrators_count;
+    arbitrators
+        .iter()
+        .enumerate()
+        .map(|(i, arbitrator)| {
+            let amount = if i == 0 {
+                arbitrator_share + remainder
+            } else {
+                arbitrator_share
+            };
+            (arbitrator.clone(), amount)
+        })
+        .collect()
+}
+
+// Records the settlement of a dispute on the stats of the arbitrators that decided it
+fn record_dispute_resolution(
+    storage: &mut dyn Storage,
+    trade: &Trade,
+    fee_shares: &[(Addr, Uint128)],
+    block_time: u64,
+) -> StdResult<()> {
+    let resolution_time = block_time - trade.disputed_at().unwrap_or(block_time);
+    let denom = denom_to_string(&trade.denom);
+    for (arbitrator, fee) in fee_shares {
+        let mut stats = ArbitratorModel::stats(storage, arbitrator);
+        let fee = Coin::new(fee.u128(), denom.clone());
+        stats.register_resolution(&trade.get_state(), resolution_time, fee);
+        stats.last_dispute_closed_at = block_time;
+        ARBITRATOR_STATS.save(storage, arbitrator, &stats)?;
+    }
+    Ok(())
+}
+
+// The arbitrators a dispute can be drawn from are fixed when it is opened or appealed, so
+// conflicts, availability or load changed once the beacon round is public can't steer the draw
+fn snapshot_dispute_candidates(
+    storage: &dyn Storage,
+    trade: &mut Trade,
+) -> Result<(), ContractError> {
+    let mut excluded = dispute_exclusions(storage, &trade.buyer, &trade.seller);
+    // Appeals are reviewed by other arbitrators than the ones that decided
+    if let Some(decision) = &trade.decision {
+        excluded.extend(decision.arbitrators.clone());
+    }
+    trade.dispute_candidates = ArbitratorModel::dispute_candidates(storage, &trade.fiat, &excluded);
+    if trade.dispute_candidates.is_empty() {
+        return Err(ContractError::NoArbitratorAvailable {
+            fiat: trade.fiat.clone(),
+        });
+    }
+    Ok(())
+}
+
+// The parties of a trade and the arbitrators on their conflict lists can't decide its disputes
+fn dispute_exclusions(storage: &dyn Storage, buyer: &Addr, seller: &Addr) -> Vec<Addr> {
+    let mut excluded = vec![buyer.clone(), seller.clone()];
+    for party in [buyer, seller] {
+        let conflicts = ArbitratorModel::query_conflicts(storage, party.clone());
+        excluded.append(&mut conflicts.unwrap_or_default());
+    }
+    excluded
+}
+
+// Arbitrators are only drawn from a randomness beacon, block data is known to the proposer
+fn randomness_beacon(hub_config: &HubConfig) -> Result<&RandomnessBeacon, ContractError> {
+    hub_config
+        .randomness_beacon
+        .as_ref()
+        .ok_or(ContractError::RandomnessBeaconNotConfigured {})
+}
+
+// The arbitrator deciding a dispute, unless it is still waiting on a beacon round
+fn drawn_arbitrator(trade: &Trade) -> Result<Addr, ContractError> {
+    match (&trade.arbitrator, trade.arbitrator_round) {
+        (Some(arbitrator), None) => Ok(arbitrator.clone()),
+        _ => Err(ContractError::ArbitratorNotDrawn { trade_id: trade.id }),
+    }
+}
+
+fn display_arbitrator(trade: &Trade) -> String {
+    trade.arbitrator.as_ref().map(Addr::to_string).unwrap_or_default()
+}
+
 // Create sub messages for updating trades count fields on maker and taker profiles
 fn create_update_trades_count_msgs(
     profile_addr: String,
@@ -1293,23 +3510,18 @@ fn add_protocol_fees_msgs(
 }
 //endregion
 
-use localmoney_protocol::trade::{trades, TradesCount, FiatTradesCount};
+use localmoney_protocol::trade::{
+    FiatTradesCount, TradesCount, TRADES_COUNT_BY_FIAT_STATE, TRADES_COUNT_BY_STATE,
+};
 
 /// Count trades by their states
 fn count_trades_by_states(deps: Deps, states: Vec<TradeState>) -> StdResult<TradesCount> {
     let mut count = 0u64;
-    
-    // Iterate through all trades and count those matching the requested states
-    let all_trades = trades()
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending);
-    
-    for result in all_trades {
-        let (_, trade) = result?;
-        if states.contains(&trade.get_state()) {
-            count += 1;
-        }
+    for state in states {
+        count += TRADES_COUNT_BY_STATE
+            .may_load(deps.storage, &state.to_string())?
+            .unwrap_or_default();
     }
-    
     Ok(TradesCount { count })
 }
 
@@ -1320,18 +3532,13 @@ fn count_trades_by_fiat_and_states(
     states: Vec<TradeState>,
 ) -> StdResult<FiatTradesCount> {
     let mut count = 0u64;
-    
-    // Iterate through all trades and count those matching both fiat and states
-    let all_trades = trades()
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending);
-    
-    for result in all_trades {
-        let (_, trade) = result?;
-        if trade.fiat == fiat && states.contains(&trade.get_state()) {
-            count += 1;
-        }
+    let fiat_key = fiat.to_string();
+    for state in states {
+        count += TRADES_COUNT_BY_FIAT_STATE
+            .may_load(deps.storage, (&fiat_key, &state.to_string()))?
+            .map(|fiat_count| fiat_count.count)
+            .unwrap_or_default();
     }
-    
     Ok(FiatTradesCount { fiat, count })
 }
 
@@ -1340,28 +3547,25 @@ fn count_all_fiats_trades(
     deps: Deps,
     states: Vec<TradeState>,
 ) -> StdResult<Vec<FiatTradesCount>> {
-    use std::collections::HashMap;
-    
-    let mut fiat_counts: HashMap<FiatCurrency, u64> = HashMap::new();
-    
-    // Iterate through all trades and count by fiat currency
-    let all_trades = trades()
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending);
-    
-    for result in all_trades {
-        let (_, trade) = result?;
-        if states.contains(&trade.get_state()) {
-            *fiat_counts.entry(trade.fiat).or_insert(0) += 1;
+    let mut result: Vec<FiatTradesCount> = vec![];
+
+    // The counters are keyed by (fiat, state), so we only go through the fiats that have trades
+    let state_keys: Vec<String> = states.iter().map(|state| state.to_string()).collect();
+    let fiat_counts =
+        TRADES_COUNT_BY_FIAT_STATE.range(deps.storage, None, None, cosmwasm_std::Order::Ascending);
+    for item in fiat_counts {
+        let ((_, state_key), fiat_count) = item?;
+        if !state_keys.contains(&state_key) || fiat_count.count.eq(&0) {
+            continue;
+        }
+        match result.iter_mut().find(|c| c.fiat.eq(&fiat_count.fiat)) {
+            Some(entry) => entry.count += fiat_count.count,
+            None => result.push(fiat_count),
         }
     }
-    
-    // Convert to vector and sort by count (descending)
-    let mut result: Vec<FiatTradesCount> = fiat_counts
-        .into_iter()
-        .map(|(fiat, count)| FiatTradesCount { fiat, count })
-        .collect();
-    
+
+    // Sort by count (descending)
     result.sort_by(|a, b| b.count.cmp(&a.count));
-    
+
     Ok(result)
 }
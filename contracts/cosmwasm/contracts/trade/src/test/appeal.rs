@@ -0,0 +1,140 @@
+use super::*;
+use localmoney_protocol::trade::TradeState;
+
+const ARBITRATOR_BOND: u128 = 2_000_000;
+const SLASH: u128 = 1_000_000;
+// 10% of the trade amount
+const APPEAL_BOND: u128 = 1_000_000;
+
+// Two bonded arbitrators, every trade decision can be appealed until round 1000
+fn appeal_suite() -> (Suite, Addr, Addr) {
+    let mut suite = Suite::with_config(|config| {
+        config.arbitrator_bond = Uint128::new(ARBITRATOR_BOND);
+        config.appeal_threshold = Uint128::new(1);
+        config.appeal_window = ROUND_1000_TIME - ROUND_2_TIME;
+        config.appeal_bond_pct = Decimal::percent(10);
+        config.overturned_decision_slash = Uint128::new(SLASH);
+    });
+    let first = suite.bonded_arbitrator("first", ARBITRATOR_BOND);
+    let second = suite.bonded_arbitrator("second", ARBITRATOR_BOND);
+    (suite, first, second)
+}
+
+// The first arbitrator decides for the maker and the taker appeals, the appeal is drawn
+// from round 1000. Returns the trade, the deciding and the reviewing arbitrators.
+fn appealed_trade(suite: &mut Suite, first: Addr, second: Addr) -> (u64, Addr, Addr) {
+    let trade_id = suite.disputed_trade();
+    let decided_by = suite.trade(trade_id).arbitrator.unwrap();
+    let reviewed_by = if decided_by.eq(&first) { second } else { first };
+    let maker = suite.maker.clone();
+    let taker = suite.taker.clone();
+    suite
+        .execute(
+            &decided_by,
+            ExecuteMsg::SettleDispute {
+                trade_id,
+                winner: maker.clone(),
+                decision_hash: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(suite.trade(trade_id).state, TradeState::DisputeDecided);
+    // The decision waits for the appeal window
+    let err = suite
+        .execute(&taker, ExecuteMsg::FinalizeSettlement { trade_id })
+        .unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::AppealWindowOpen { .. }
+    ));
+
+    // Only the losing party can appeal, with the full bond
+    suite.set_time(ROUND_1000_TIME);
+    let appeal = ExecuteMsg::AppealDispute { trade_id };
+    assert!(suite
+        .execute_with_funds(&maker, appeal.clone(), APPEAL_BOND)
+        .is_err());
+    let err = suite
+        .execute_with_funds(&taker, appeal.clone(), APPEAL_BOND - 1)
+        .unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::InsufficientAppealBond { .. }
+    ));
+    suite
+        .execute_with_funds(&taker, appeal, APPEAL_BOND)
+        .unwrap();
+    let trade = suite.trade(trade_id);
+    assert_eq!(trade.state, TradeState::EscrowDisputed);
+    assert_eq!(trade.arbitrator, None);
+
+    suite.draw_arbitrator(trade_id, 1000).unwrap();
+    assert_eq!(suite.trade(trade_id).arbitrator, Some(reviewed_by.clone()));
+    (trade_id, decided_by, reviewed_by)
+}
+
+#[test]
+fn upheld_appeal_pays_the_bond_to_the_deciding_arbitrator() {
+    let (mut suite, first, second) = appeal_suite();
+    let (trade_id, decided_by, reviewed_by) = appealed_trade(&mut suite, first, second);
+    let maker = suite.maker.clone();
+    let taker = suite.taker.clone();
+
+    suite
+        .execute(
+            &reviewed_by,
+            ExecuteMsg::SettleDispute {
+                trade_id,
+                winner: maker.clone(),
+                decision_hash: None,
+            },
+        )
+        .unwrap();
+    // A decision taken on appeal is final
+    assert_eq!(suite.trade(trade_id).state, TradeState::SettledForMaker);
+    assert_eq!(
+        suite.balance(&maker),
+        INITIAL_BALANCE - 3 * PROTOCOL_FEE - ARBITRATION_FEE
+    );
+    assert_eq!(suite.balance(&taker), INITIAL_BALANCE - APPEAL_BOND);
+    assert_eq!(suite.balance(&decided_by), APPEAL_BOND);
+    assert_eq!(suite.balance(&reviewed_by), ARBITRATION_FEE);
+    let bond = suite.arbitrator_bond(&decided_by).unwrap();
+    assert_eq!(bond.amount, Uint128::new(ARBITRATOR_BOND));
+    // Only the arbitrator bonds are left
+    assert_eq!(suite.balance(&suite.trade), 2 * ARBITRATOR_BOND);
+}
+
+#[test]
+fn reversed_appeal_refunds_the_bond_and_slashes_the_deciding_arbitrator() {
+    let (mut suite, first, second) = appeal_suite();
+    let (trade_id, decided_by, reviewed_by) = appealed_trade(&mut suite, first, second);
+    let maker = suite.maker.clone();
+    let taker = suite.taker.clone();
+
+    suite
+        .execute(
+            &reviewed_by,
+            ExecuteMsg::SettleDispute {
+                trade_id,
+                winner: taker.clone(),
+                decision_hash: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(suite.trade(trade_id).state, TradeState::SettledForTaker);
+    assert_eq!(
+        suite.balance(&taker),
+        INITIAL_BALANCE + TRADE_AMOUNT - ARBITRATION_FEE + SLASH
+    );
+    assert_eq!(
+        suite.balance(&maker),
+        INITIAL_BALANCE - TRADE_AMOUNT - 3 * PROTOCOL_FEE
+    );
+    assert_eq!(suite.balance(&decided_by), 0);
+    assert_eq!(suite.balance(&reviewed_by), ARBITRATION_FEE);
+    let bond = suite.arbitrator_bond(&decided_by).unwrap();
+    assert_eq!(bond.amount, Uint128::new(ARBITRATOR_BOND - SLASH));
+    assert_eq!(bond.slashed_amount, Uint128::new(SLASH));
+    assert_eq!(suite.balance(&suite.trade), 2 * ARBITRATOR_BOND - SLASH);
+}
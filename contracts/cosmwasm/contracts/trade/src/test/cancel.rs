@@ -0,0 +1,73 @@
+use super::*;
+use localmoney_protocol::trade::TradeState;
+
+#[test]
+fn confirmed_cancel_refunds_the_seller_with_fees() {
+    let mut suite = Suite::new();
+    suite.add_arbitrator("arbitrator");
+    let trade_id = suite.funded_trade();
+    let maker = suite.maker.clone();
+    let taker = suite.taker.clone();
+
+    suite
+        .execute(&maker, ExecuteMsg::ProposeCancel { trade_id })
+        .unwrap();
+    assert_eq!(suite.trade(trade_id).state, TradeState::EscrowFunded);
+
+    // Only the counterparty can confirm the proposal
+    let err = suite
+        .execute(&maker, ExecuteMsg::ConfirmCancel { trade_id })
+        .unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::CancelNotProposed { .. }
+    ));
+    let stranger = suite.addr("stranger");
+    assert!(suite
+        .execute(&stranger, ExecuteMsg::ConfirmCancel { trade_id })
+        .is_err());
+
+    suite
+        .execute(&taker, ExecuteMsg::ConfirmCancel { trade_id })
+        .unwrap();
+    assert_eq!(suite.trade(trade_id).state, TradeState::EscrowRefunded);
+    assert_eq!(suite.balance(&maker), INITIAL_BALANCE);
+    assert_eq!(suite.balance(&taker), INITIAL_BALANCE);
+    assert_eq!(suite.balance(&suite.warchest), 0);
+    assert_eq!(suite.balance(&suite.trade), 0);
+}
+
+#[test]
+fn cancel_proposal_only_holds_for_the_state_it_was_made_in() {
+    let mut suite = Suite::new();
+    suite.add_arbitrator("arbitrator");
+    let trade_id = suite.funded_trade();
+    let maker = suite.maker.clone();
+    let taker = suite.taker.clone();
+
+    suite
+        .execute(&taker, ExecuteMsg::ProposeCancel { trade_id })
+        .unwrap();
+    suite
+        .execute(&taker, ExecuteMsg::FiatDeposited { trade_id })
+        .unwrap();
+    let err = suite
+        .execute(&maker, ExecuteMsg::ConfirmCancel { trade_id })
+        .unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::CancelNotProposed { .. }
+    ));
+    assert_eq!(suite.trade(trade_id).state, TradeState::FiatDeposited);
+
+    // A new proposal can still be confirmed after the fiat was deposited
+    suite
+        .execute(&maker, ExecuteMsg::ProposeCancel { trade_id })
+        .unwrap();
+    suite
+        .execute(&taker, ExecuteMsg::ConfirmCancel { trade_id })
+        .unwrap();
+    assert_eq!(suite.trade(trade_id).state, TradeState::EscrowRefunded);
+    assert_eq!(suite.balance(&maker), INITIAL_BALANCE);
+    assert_eq!(suite.balance(&suite.trade), 0);
+}
@@ -0,0 +1,65 @@
+use super::*;
+use localmoney_protocol::constants::MAX_DISPUTE_EVIDENCE_ITEMS;
+use localmoney_protocol::trade::{DisputeEvidence, TradeState};
+
+fn submit_evidence(suite: &mut Suite, sender: &Addr, trade_id: u64) -> anyhow::Result<AppResponse> {
+    suite.execute(
+        sender,
+        ExecuteMsg::SubmitEvidence {
+            trade_id,
+            evidence_hash: "ab".repeat(32),
+            uri: None,
+        },
+    )
+}
+
+#[test]
+fn evidence_cap_is_per_author() {
+    let mut suite = Suite::new();
+    let arbitrator = suite.add_arbitrator("arbitrator");
+    let trade_id = suite.disputed_trade();
+
+    // The seller fills its share of the log
+    let maker = suite.maker.clone();
+    for _ in 0..MAX_DISPUTE_EVIDENCE_ITEMS {
+        submit_evidence(&mut suite, &maker, trade_id).unwrap();
+    }
+    let err = submit_evidence(&mut suite, &maker, trade_id).unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::DisputeEvidenceLimitReached { .. }
+    ));
+
+    // The buyer and the arbitrator can still add to it
+    let taker = suite.taker.clone();
+    submit_evidence(&mut suite, &taker, trade_id).unwrap();
+    suite
+        .execute(
+            &arbitrator,
+            ExecuteMsg::RequestEvidence {
+                trade_id,
+                party: maker.clone(),
+                description: "Bank statement".to_string(),
+            },
+        )
+        .unwrap();
+    let evidence: Vec<DisputeEvidence> = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.trade,
+            &QueryMsg::DisputeEvidence {
+                trade_id,
+                start_after: Some(MAX_DISPUTE_EVIDENCE_ITEMS - 1),
+                limit: 10,
+            },
+        )
+        .unwrap();
+    let authors: Vec<Addr> = evidence.into_iter().map(|entry| entry.author).collect();
+    assert_eq!(authors, vec![taker.clone(), arbitrator.clone()]);
+
+    // Evidence doesn't move the escrow
+    let trade = suite.trade(trade_id);
+    assert_eq!(trade.state, TradeState::EscrowDisputed);
+    assert_eq!(suite.balance(&suite.trade), TRADE_AMOUNT + 3 * PROTOCOL_FEE);
+}
@@ -0,0 +1,112 @@
+use super::*;
+use localmoney_protocol::trade::{TradeModel, TradeState};
+
+#[test]
+fn expired_trades_are_swept_and_maker_fees_pay_the_bounty() {
+    let mut suite = Suite::with_config(|config| {
+        config.expiration_bounty_pct = Decimal::percent(50);
+    });
+    let maker = suite.maker.clone();
+    let taker = suite.taker.clone();
+    let keeper = suite.addr("keeper");
+    suite.add_arbitrator("arbitrator");
+
+    // A sell offer escrow funded by the maker with the fees on top
+    let funded_by_maker = suite.funded_trade();
+    // A buy offer escrow funded by the taker, without fees
+    let buy_offer_id = suite.create_offer(OfferType::Buy);
+    let funded_by_taker = suite.create_trade(buy_offer_id, TRADE_AMOUNT);
+    suite
+        .execute(
+            &maker,
+            ExecuteMsg::AcceptRequest {
+                trade_id: funded_by_taker,
+                maker_contact: "maker_contact".to_string(),
+            },
+        )
+        .unwrap();
+    suite
+        .execute_with_funds(
+            &taker,
+            ExecuteMsg::FundEscrow {
+                trade_id: funded_by_taker,
+                maker_contact: None,
+            },
+            TRADE_AMOUNT,
+        )
+        .unwrap();
+    // A request that was never funded
+    let sell_offer_id = suite.trade(funded_by_maker).offer_id;
+    let request = suite.create_trade(sell_offer_id, TRADE_AMOUNT);
+    assert_eq!(
+        suite.balance(&suite.trade),
+        2 * TRADE_AMOUNT + 3 * PROTOCOL_FEE
+    );
+
+    // Nothing has expired yet
+    suite
+        .execute(&keeper, ExecuteMsg::ExpireTrades { limit: 10 })
+        .unwrap();
+    assert_eq!(suite.trade(request).state, TradeState::RequestCreated);
+
+    suite.set_time(START_TIME + PHASE_TIMER + 1);
+    suite
+        .execute(&keeper, ExecuteMsg::ExpireTrades { limit: 10 })
+        .unwrap();
+    assert_eq!(
+        suite.trade(funded_by_maker).state,
+        TradeState::EscrowRefunded
+    );
+    assert_eq!(
+        suite.trade(funded_by_taker).state,
+        TradeState::EscrowRefunded
+    );
+    assert_eq!(suite.trade(request).state, TradeState::RequestExpired);
+
+    // Half of the warchest fee goes to the keeper, the maker gets the rest of the fees back
+    let bounty = PROTOCOL_FEE / 2;
+    assert_eq!(suite.balance(&keeper), bounty);
+    assert_eq!(suite.balance(&maker), INITIAL_BALANCE - bounty);
+    assert_eq!(suite.balance(&taker), INITIAL_BALANCE);
+    assert_eq!(suite.balance(&suite.warchest), 0);
+    assert_eq!(suite.balance(&suite.trade), 0);
+
+    // Swept trades aren't expired again
+    suite
+        .execute(&keeper, ExecuteMsg::ExpireTrades { limit: 10 })
+        .unwrap();
+    assert_eq!(suite.balance(&keeper), bounty);
+}
+
+#[test]
+fn migration_restores_the_maker_fees_of_funded_escrows() {
+    let mut suite = Suite::with_config(|config| {
+        config.expiration_bounty_pct = Decimal::percent(50);
+    });
+    suite.add_arbitrator("arbitrator");
+    let trade_id = suite.funded_trade();
+
+    // Escrows funded before the fees were stored on the trade
+    let mut storage = suite.app.contract_storage_mut(&suite.trade);
+    let mut trade = TradeModel::from_store(storage.as_ref(), trade_id);
+    trade.escrow_fees = Uint128::zero();
+    TradeModel::store(storage.as_mut(), &trade).unwrap();
+    TradeModel::backfill_escrow_fees(storage.as_mut(), |_| Uint128::new(3 * PROTOCOL_FEE)).unwrap();
+    let trade = TradeModel::from_store(storage.as_ref(), trade_id);
+    assert_eq!(trade.escrow_fees, Uint128::new(3 * PROTOCOL_FEE));
+    drop(storage);
+
+    // The maker gets its fees back minus the bounty
+    let keeper = suite.addr("keeper");
+    suite.set_time(START_TIME + PHASE_TIMER + 1);
+    suite
+        .execute(&keeper, ExecuteMsg::ExpireTrades { limit: 10 })
+        .unwrap();
+    assert_eq!(suite.trade(trade_id).state, TradeState::EscrowRefunded);
+    assert_eq!(suite.balance(&keeper), PROTOCOL_FEE / 2);
+    assert_eq!(
+        suite.balance(&suite.maker),
+        INITIAL_BALANCE - PROTOCOL_FEE / 2
+    );
+    assert_eq!(suite.balance(&suite.trade), 0);
+}
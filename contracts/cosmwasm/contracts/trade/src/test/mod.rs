@@ -0,0 +1,443 @@
+// Trade flows run against the hub, offer, profile and price contracts.
+mod appeal;
+mod cancel;
+mod evidence;
+mod expiration;
+mod panel;
+mod partial_release;
+mod reassignment;
+mod split_settlement;
+mod unbonding;
+
+use cosmwasm_std::{coin, coins, Addr, Binary, Decimal, Empty, HexBinary, Timestamp, Uint128};
+use cw20::Denom;
+use cw_multi_test::{App, AppResponse, BankSudo, Contract, ContractWrapper, Executor, SudoMsg};
+use localmoney_protocol::currencies::FiatCurrency;
+use localmoney_protocol::errors::ContractError;
+use localmoney_protocol::hub::HubConfig;
+use localmoney_protocol::offer::{OfferMsg, OfferType, TradeInfo};
+use localmoney_protocol::randomness::RandomnessBeacon;
+use localmoney_protocol::trade::{ArbitratorBond, ExecuteMsg, NewTrade, QueryMsg, TradeResponse};
+
+pub const DENOM: &str = "ulocal";
+pub const TRADE_AMOUNT: u128 = 10_000_000;
+pub const INITIAL_BALANCE: u128 = 1_000_000_000;
+// Each protocol fee is 1% of the trade amount, the arbitration fee 5%
+pub const PROTOCOL_FEE: u128 = 100_000;
+pub const ARBITRATION_FEE: u128 = 500_000;
+
+// drand quicknet, rounds 2 and 1000 are published at genesis and genesis + 2994
+pub const GENESIS_TIME: u64 = 1692803367;
+pub const ROUND_2_TIME: u64 = GENESIS_TIME;
+pub const ROUND_1000_TIME: u64 = GENESIS_TIME + 2994;
+const QUICKNET_PUBLIC_KEY: &str = concat!(
+    "83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c",
+    "8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb",
+    "5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a",
+);
+const QUICKNET_ROUND_2: &str = concat!(
+    "b6b6a585449b66eb12e875b64fcbab3799861a00e4dbf092d99e969a5eac57dd",
+    "3f798acf61e705fe4f093db926626807",
+);
+const QUICKNET_ROUND_1000: &str = concat!(
+    "b44679b9a59af2ec876b1a6b1ad52ea9b1615fc3982b19576350f93447cb1125",
+    "e342b73a8dd2bacbe47e4b6b63ed5e39",
+);
+
+// Trades are opened before genesis so their disputes are drawn from round 2
+pub const START_TIME: u64 = GENESIS_TIME - 1000;
+pub const PHASE_TIMER: u64 = 600;
+
+fn hub_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        hub::contract::execute,
+        hub::contract::instantiate,
+        hub::contract::query,
+    ))
+}
+
+fn offer_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        offer::contract::execute,
+        offer::contract::instantiate,
+        offer::contract::query,
+    ))
+}
+
+fn profile_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        profile::contract::execute,
+        profile::contract::instantiate,
+        profile::contract::query,
+    ))
+}
+
+fn price_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        price::contract::execute,
+        price::contract::instantiate,
+        price::contract::query,
+    ))
+}
+
+fn trade_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    ))
+}
+
+pub fn round_signature(round: u64) -> Binary {
+    let signature = match round {
+        2 => QUICKNET_ROUND_2,
+        1000 => QUICKNET_ROUND_1000,
+        _ => panic!("no quicknet signature for round {round}"),
+    };
+    HexBinary::from_hex(signature).unwrap().into()
+}
+
+pub fn contract_error(err: anyhow::Error) -> ContractError {
+    err.downcast::<ContractError>().unwrap()
+}
+
+pub struct Suite {
+    pub app: App,
+    pub admin: Addr,
+    pub offer: Addr,
+    pub trade: Addr,
+    pub maker: Addr,
+    pub taker: Addr,
+    pub chain_fee_collector: Addr,
+    pub warchest: Addr,
+}
+
+impl Suite {
+    pub fn new() -> Suite {
+        Suite::with_config(|_| {})
+    }
+
+    pub fn with_config(configure: impl FnOnce(&mut HubConfig)) -> Suite {
+        let mut app = App::default();
+        let api = app.api();
+        let admin = api.addr_make("admin");
+        let maker = api.addr_make("maker");
+        let taker = api.addr_make("taker");
+        let chain_fee_collector = api.addr_make("chain_fee_collector");
+        let warchest = api.addr_make("warchest");
+        let local_market = api.addr_make("local_market");
+        let price_provider = api.addr_make("price_provider");
+        app.update_block(|block| block.time = Timestamp::from_seconds(START_TIME));
+        app.init_modules(|router, _, storage| {
+            for user in [&maker, &taker] {
+                router
+                    .bank
+                    .init_balance(storage, user, coins(INITIAL_BALANCE, DENOM))
+                    .unwrap();
+            }
+        });
+
+        let hub_code = app.store_code(hub_contract());
+        let offer_code = app.store_code(offer_contract());
+        let profile_code = app.store_code(profile_contract());
+        let price_code = app.store_code(price_contract());
+        let trade_code = app.store_code(trade_contract());
+        let hub = app
+            .instantiate_contract(
+                hub_code,
+                admin.clone(),
+                &localmoney_protocol::hub::InstantiateMsg {
+                    admin_addr: admin.clone(),
+                },
+                &[],
+                "hub",
+                None,
+            )
+            .unwrap();
+        let offer = app
+            .instantiate_contract(offer_code, admin.clone(), &Empty {}, &[], "offer", None)
+            .unwrap();
+        let profile = app
+            .instantiate_contract(profile_code, admin.clone(), &Empty {}, &[], "profile", None)
+            .unwrap();
+        let price = app
+            .instantiate_contract(price_code, admin.clone(), &Empty {}, &[], "price", None)
+            .unwrap();
+        let trade = app
+            .instantiate_contract(trade_code, admin.clone(), &Empty {}, &[], "trade", None)
+            .unwrap();
+
+        let mut hub_config = HubConfig {
+            offer_addr: offer.clone(),
+            trade_addr: trade.clone(),
+            profile_addr: profile,
+            price_addr: price,
+            price_provider_addr: price_provider,
+            local_market_addr: local_market,
+            local_denom: Denom::Native(DENOM.to_string()),
+            chain_fee_collector_addr: chain_fee_collector.clone(),
+            warchest_addr: warchest.clone(),
+            active_offers_limit: 10,
+            active_trades_limit: 10,
+            arbitration_fee_pct: Decimal::percent(5),
+            burn_fee_pct: Decimal::percent(1),
+            chain_fee_pct: Decimal::percent(1),
+            warchest_fee_pct: Decimal::percent(1),
+            trade_expiration_timer: PHASE_TIMER,
+            trade_dispute_timer: PHASE_TIMER,
+            trade_limit_min: Uint128::new(1),
+            trade_limit_max: Uint128::new(1_000_000_000),
+            maker_presence_window: 0,
+            expiration_bounty_pct: Decimal::zero(),
+            trade_accept_timer: 0,
+            trade_funding_timer: 0,
+            trade_payment_timer: 0,
+            trade_release_timer: 0,
+            dispute_resolution_timer: PHASE_TIMER,
+            arbitration_panel_threshold: Uint128::zero(),
+            arbitration_panel_size: 0,
+            randomness_beacon: Some(RandomnessBeacon {
+                public_key: HexBinary::from_hex(QUICKNET_PUBLIC_KEY).unwrap().into(),
+                genesis_time: GENESIS_TIME,
+                period: 3,
+            }),
+            arbitrator_bond: Uint128::zero(),
+            arbitrator_unbonding_period: 0,
+            appeal_threshold: Uint128::zero(),
+            appeal_window: 0,
+            appeal_bond_pct: Decimal::zero(),
+            overturned_decision_slash: Uint128::zero(),
+        };
+        configure(&mut hub_config);
+        app.execute_contract(
+            admin.clone(),
+            hub.clone(),
+            &localmoney_protocol::hub::ExecuteMsg::UpdateConfig(hub_config),
+            &[],
+        )
+        .unwrap();
+
+        Suite {
+            app,
+            admin,
+            offer,
+            trade,
+            maker,
+            taker,
+            chain_fee_collector,
+            warchest,
+        }
+    }
+
+    pub fn addr(&self, name: &str) -> Addr {
+        self.app.api().addr_make(name)
+    }
+
+    pub fn set_time(&mut self, seconds: u64) {
+        self.app.update_block(|block| {
+            block.time = Timestamp::from_seconds(seconds);
+            block.height += 1;
+        });
+    }
+
+    pub fn balance(&self, addr: &Addr) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(addr, DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    pub fn execute(&mut self, sender: &Addr, msg: ExecuteMsg) -> anyhow::Result<AppResponse> {
+        self.execute_with_funds(sender, msg, 0)
+    }
+
+    pub fn execute_with_funds(
+        &mut self,
+        sender: &Addr,
+        msg: ExecuteMsg,
+        amount: u128,
+    ) -> anyhow::Result<AppResponse> {
+        let funds = if amount > 0 {
+            vec![coin(amount, DENOM)]
+        } else {
+            vec![]
+        };
+        self.app
+            .execute_contract(sender.clone(), self.trade.clone(), &msg, &funds)
+    }
+
+    pub fn trade(&self, trade_id: u64) -> TradeResponse {
+        let trade_info: TradeInfo = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.trade, &QueryMsg::Trade { id: trade_id })
+            .unwrap();
+        trade_info.trade
+    }
+
+    pub fn add_arbitrator(&mut self, name: &str) -> Addr {
+        let arbitrator = self.addr(name);
+        let admin = self.admin.clone();
+        self.execute(
+            &admin,
+            ExecuteMsg::NewArbitrator {
+                arbitrator: arbitrator.clone(),
+                fiat: FiatCurrency::USD,
+                encryption_key: format!("{name}_key"),
+                weight: None,
+            },
+        )
+        .unwrap();
+        arbitrator
+    }
+
+    /// An arbitrator that applied with `bond` and was approved by the admin.
+    pub fn bonded_arbitrator(&mut self, name: &str, bond: u128) -> Addr {
+        let arbitrator = self.addr(name);
+        self.app
+            .sudo(SudoMsg::Bank(BankSudo::Mint {
+                to_address: arbitrator.to_string(),
+                amount: coins(bond, DENOM),
+            }))
+            .unwrap();
+        self.execute_with_funds(
+            &arbitrator,
+            ExecuteMsg::ApplyArbitrator {
+                fiats: vec![FiatCurrency::USD],
+                languages: vec![],
+                encryption_key: format!("{name}_key"),
+            },
+            bond,
+        )
+        .unwrap();
+        let admin = self.admin.clone();
+        self.execute(
+            &admin,
+            ExecuteMsg::ApproveArbitrator {
+                applicant: arbitrator.clone(),
+            },
+        )
+        .unwrap();
+        arbitrator
+    }
+
+    pub fn arbitrator_bond(&self, arbitrator: &Addr) -> Option<ArbitratorBond> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                &self.trade,
+                &QueryMsg::ArbitratorBond {
+                    arbitrator: arbitrator.clone(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn create_offer(&mut self, offer_type: OfferType) -> u64 {
+        let offer = OfferMsg {
+            offer_type,
+            owner_contact: "maker_contact".to_string(),
+            owner_encryption_key: "maker_key".to_string(),
+            fiat_currency: FiatCurrency::USD,
+            rate: Uint128::new(100),
+            denom: Denom::Native(DENOM.to_string()),
+            min_amount: Uint128::new(1_000_000),
+            max_amount: Uint128::new(100_000_000),
+            description: None,
+            expires_at: None,
+            payment_type: Default::default(),
+            location: None,
+            schedule: None,
+            timers: None,
+            payment_attestation: false,
+        };
+        let res = self
+            .app
+            .execute_contract(
+                self.maker.clone(),
+                self.offer.clone(),
+                &localmoney_protocol::offer::ExecuteMsg::Create { offer },
+                &[],
+            )
+            .unwrap();
+        event_attribute(&res, "id").parse().unwrap()
+    }
+
+    pub fn create_trade(&mut self, offer_id: u64, amount: u128) -> u64 {
+        let taker = self.taker.clone();
+        let res = self
+            .execute(
+                &taker,
+                ExecuteMsg::Create(NewTrade {
+                    offer_id,
+                    amount: Uint128::new(amount),
+                    taker: taker.clone(),
+                    profile_taker_contact: "taker_contact".to_string(),
+                    profile_taker_encryption_key: "taker_key".to_string(),
+                    taker_contact: "taker_contact".to_string(),
+                }),
+            )
+            .unwrap();
+        event_attribute(&res, "trade_id").parse().unwrap()
+    }
+
+    /// A trade on a sell offer funded by the maker, who pays the fees on top of the amount.
+    pub fn funded_trade(&mut self) -> u64 {
+        let offer_id = self.create_offer(OfferType::Sell);
+        let trade_id = self.create_trade(offer_id, TRADE_AMOUNT);
+        let maker = self.maker.clone();
+        self.execute_with_funds(
+            &maker,
+            ExecuteMsg::FundEscrow {
+                trade_id,
+                maker_contact: Some("maker_contact".to_string()),
+            },
+            TRADE_AMOUNT + 3 * PROTOCOL_FEE,
+        )
+        .unwrap();
+        trade_id
+    }
+
+    /// A funded trade disputed by the buyer once the release window is over, with its
+    /// arbitrators drawn from round 2.
+    pub fn disputed_trade(&mut self) -> u64 {
+        let trade_id = self.funded_trade();
+        let taker = self.taker.clone();
+        self.execute(&taker, ExecuteMsg::FiatDeposited { trade_id })
+            .unwrap();
+        self.set_time(ROUND_2_TIME);
+        self.execute(
+            &taker,
+            ExecuteMsg::DisputeEscrow {
+                trade_id,
+                buyer_contact: None,
+                seller_contact: None,
+            },
+        )
+        .unwrap();
+        self.draw_arbitrator(trade_id, 2).unwrap();
+        trade_id
+    }
+
+    pub fn draw_arbitrator(&mut self, trade_id: u64, round: u64) -> anyhow::Result<AppResponse> {
+        let keeper = self.addr("keeper");
+        self.execute(
+            &keeper,
+            ExecuteMsg::DrawArbitrator {
+                trade_id,
+                signature: round_signature(round),
+            },
+        )
+    }
+}
+
+pub fn event_attribute(res: &AppResponse, key: &str) -> String {
+    res.events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .find(|attribute| attribute.key.eq(key))
+        .map(|attribute| attribute.value.clone())
+        .unwrap()
+}
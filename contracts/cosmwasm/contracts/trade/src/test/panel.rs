@@ -0,0 +1,92 @@
+use super::*;
+use localmoney_protocol::trade::TradeState;
+
+// Three arbitrators, with panels of three for trades worth at least `threshold` USD
+fn panel_suite(threshold: u128) -> Suite {
+    let mut suite = Suite::with_config(|config| {
+        config.arbitration_panel_threshold = Uint128::new(threshold);
+        config.arbitration_panel_size = 3;
+    });
+    for name in ["first", "second", "third"] {
+        suite.add_arbitrator(name);
+    }
+    suite
+}
+
+#[test]
+fn panel_majority_settles_and_shares_the_arbitration_fee() {
+    let mut suite = panel_suite(TRADE_AMOUNT / 100);
+    let trade_id = suite.disputed_trade();
+    let maker = suite.maker.clone();
+    let taker = suite.taker.clone();
+    let trade = suite.trade(trade_id);
+    assert_eq!(trade.panel.len(), 3);
+    assert_eq!(trade.arbitrator.as_ref(), trade.panel.first());
+    let (first, second, third) = (
+        trade.panel[0].clone(),
+        trade.panel[1].clone(),
+        trade.panel[2].clone(),
+    );
+
+    // Panel disputes are only settled through votes
+    let err = suite
+        .execute(
+            &first,
+            ExecuteMsg::SettleDispute {
+                trade_id,
+                winner: taker.clone(),
+                decision_hash: None,
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::DisputeHasPanel { .. }
+    ));
+
+    let vote = |winner: &Addr| ExecuteMsg::VoteDispute {
+        trade_id,
+        winner: winner.clone(),
+        decision_hash: None,
+    };
+    suite.execute(&first, vote(&taker)).unwrap();
+    let err = suite.execute(&first, vote(&taker)).unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::DisputeAlreadyVoted { .. }
+    ));
+    assert!(suite.execute(&maker, vote(&maker)).is_err());
+
+    // A split vote keeps the dispute open
+    suite.execute(&second, vote(&maker)).unwrap();
+    let trade = suite.trade(trade_id);
+    assert_eq!(trade.state, TradeState::EscrowDisputed);
+    assert_eq!(trade.votes.len(), 2);
+    assert_eq!(suite.balance(&suite.trade), TRADE_AMOUNT + 3 * PROTOCOL_FEE);
+
+    suite.execute(&third, vote(&taker)).unwrap();
+    assert_eq!(suite.trade(trade_id).state, TradeState::SettledForTaker);
+    assert_eq!(
+        suite.balance(&taker),
+        INITIAL_BALANCE + TRADE_AMOUNT - ARBITRATION_FEE
+    );
+    assert_eq!(
+        suite.balance(&maker),
+        INITIAL_BALANCE - TRADE_AMOUNT - 3 * PROTOCOL_FEE
+    );
+    // Only the majority shares the arbitration fee
+    assert_eq!(suite.balance(&first), ARBITRATION_FEE / 2);
+    assert_eq!(suite.balance(&second), 0);
+    assert_eq!(suite.balance(&third), ARBITRATION_FEE / 2);
+    assert_eq!(suite.balance(&suite.warchest), PROTOCOL_FEE);
+    assert_eq!(suite.balance(&suite.trade), 0);
+}
+
+#[test]
+fn panel_is_not_drawn_below_the_threshold() {
+    let mut suite = panel_suite(TRADE_AMOUNT / 100 + 1);
+    let trade_id = suite.disputed_trade();
+    let trade = suite.trade(trade_id);
+    assert!(trade.panel.is_empty());
+    assert!(trade.arbitrator.is_some());
+}
@@ -0,0 +1,119 @@
+use super::*;
+use localmoney_protocol::trade::TradeState;
+
+const RELEASED: u128 = 4_000_000;
+// Each protocol fee on the released amount
+const RELEASED_FEE: u128 = RELEASED / 100;
+
+#[test]
+fn partial_release_refunds_the_rest_and_unused_maker_fees() {
+    let mut suite = Suite::new();
+    suite.add_arbitrator("arbitrator");
+    let trade_id = suite.funded_trade();
+    let maker = suite.maker.clone();
+    let taker = suite.taker.clone();
+    suite
+        .execute(&taker, ExecuteMsg::FiatDeposited { trade_id })
+        .unwrap();
+
+    // A full release goes through ReleaseEscrow
+    let err = suite
+        .execute(
+            &maker,
+            ExecuteMsg::PartialRelease {
+                trade_id,
+                amount: Uint128::new(TRADE_AMOUNT),
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::InvalidParameter { .. }
+    ));
+
+    suite
+        .execute(
+            &maker,
+            ExecuteMsg::PartialRelease {
+                trade_id,
+                amount: Uint128::new(RELEASED),
+            },
+        )
+        .unwrap();
+    let trade = suite.trade(trade_id);
+    assert_eq!(trade.state, TradeState::EscrowPartiallyReleased);
+    assert_eq!(trade.released_amount, Some(Uint128::new(RELEASED)));
+
+    // The maker only pays the fees of the released amount
+    assert_eq!(suite.balance(&taker), INITIAL_BALANCE + RELEASED);
+    assert_eq!(
+        suite.balance(&maker),
+        INITIAL_BALANCE - RELEASED - 3 * RELEASED_FEE
+    );
+    assert_eq!(suite.balance(&suite.warchest), RELEASED_FEE);
+    assert_eq!(suite.balance(&suite.chain_fee_collector), RELEASED_FEE);
+    assert_eq!(suite.balance(&suite.trade), 0);
+}
+
+#[test]
+fn partial_release_to_a_maker_buyer_deducts_the_fees() {
+    let mut suite = Suite::new();
+    suite.add_arbitrator("arbitrator");
+    let maker = suite.maker.clone();
+    let taker = suite.taker.clone();
+    let offer_id = suite.create_offer(OfferType::Buy);
+    let trade_id = suite.create_trade(offer_id, TRADE_AMOUNT);
+    suite
+        .execute(
+            &maker,
+            ExecuteMsg::AcceptRequest {
+                trade_id,
+                maker_contact: "maker_contact".to_string(),
+            },
+        )
+        .unwrap();
+    suite
+        .execute_with_funds(
+            &taker,
+            ExecuteMsg::FundEscrow {
+                trade_id,
+                maker_contact: None,
+            },
+            TRADE_AMOUNT,
+        )
+        .unwrap();
+    suite
+        .execute(&maker, ExecuteMsg::FiatDeposited { trade_id })
+        .unwrap();
+
+    // Only the seller can release
+    assert!(suite
+        .execute(
+            &maker,
+            ExecuteMsg::PartialRelease {
+                trade_id,
+                amount: Uint128::new(RELEASED),
+            },
+        )
+        .is_err());
+    suite
+        .execute(
+            &taker,
+            ExecuteMsg::PartialRelease {
+                trade_id,
+                amount: Uint128::new(RELEASED),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        suite.trade(trade_id).state,
+        TradeState::EscrowPartiallyReleased
+    );
+    assert_eq!(
+        suite.balance(&maker),
+        INITIAL_BALANCE + RELEASED - 3 * RELEASED_FEE
+    );
+    assert_eq!(suite.balance(&taker), INITIAL_BALANCE - RELEASED);
+    assert_eq!(suite.balance(&suite.warchest), RELEASED_FEE);
+    assert_eq!(suite.balance(&suite.trade), 0);
+}
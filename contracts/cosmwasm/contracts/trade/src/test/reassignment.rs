@@ -0,0 +1,100 @@
+use super::*;
+use localmoney_protocol::trade::TradeState;
+
+#[test]
+fn reassignment_without_another_arbitrator_keeps_the_dispute() {
+    let mut suite = Suite::new();
+    let arbitrator = suite.add_arbitrator("arbitrator");
+    let trade_id = suite.disputed_trade();
+    assert_eq!(suite.trade(trade_id).arbitrator, Some(arbitrator.clone()));
+
+    // The deadline passed but no other arbitrator can take the dispute
+    suite.set_time(ROUND_1000_TIME);
+    let taker = suite.taker.clone();
+    let err = suite
+        .execute(&taker, ExecuteMsg::ReassignArbitrator { trade_id })
+        .unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::NoArbitratorAvailable { .. }
+    ));
+    let trade = suite.trade(trade_id);
+    assert_eq!(trade.state, TradeState::EscrowDisputed);
+    assert_eq!(trade.arbitrator, Some(arbitrator.clone()));
+    assert!(suite.draw_arbitrator(trade_id, 1000).is_err());
+
+    // The arbitrator can still settle the dispute
+    suite
+        .execute(
+            &arbitrator,
+            ExecuteMsg::SettleDispute {
+                trade_id,
+                winner: taker.clone(),
+                decision_hash: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(suite.trade(trade_id).state, TradeState::SettledForTaker);
+    assert_eq!(
+        suite.balance(&taker),
+        INITIAL_BALANCE + TRADE_AMOUNT - ARBITRATION_FEE
+    );
+    assert_eq!(suite.balance(&arbitrator), ARBITRATION_FEE);
+    assert_eq!(suite.balance(&suite.warchest), PROTOCOL_FEE);
+    assert_eq!(suite.balance(&suite.chain_fee_collector), PROTOCOL_FEE);
+    assert_eq!(suite.balance(&suite.trade), 0);
+}
+
+#[test]
+fn reassignment_moves_the_dispute_to_another_arbitrator() {
+    let mut suite = Suite::new();
+    let first = suite.add_arbitrator("first");
+    let second = suite.add_arbitrator("second");
+    let trade_id = suite.disputed_trade();
+    let previous = suite.trade(trade_id).arbitrator.unwrap();
+    let next = if previous.eq(&first) { second } else { first };
+
+    // The dispute can't be moved before its deadline
+    let taker = suite.taker.clone();
+    let err = suite
+        .execute(&taker, ExecuteMsg::ReassignArbitrator { trade_id })
+        .unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::DisputeDeadlineNotReached { .. }
+    ));
+
+    suite.set_time(ROUND_1000_TIME);
+    suite
+        .execute(&taker, ExecuteMsg::ReassignArbitrator { trade_id })
+        .unwrap();
+    // The previous arbitrator can't settle while the replacement is drawn
+    let winner = suite.maker.clone();
+    let settle = ExecuteMsg::SettleDispute {
+        trade_id,
+        winner: winner.clone(),
+        decision_hash: None,
+    };
+    let err = suite.execute(&previous, settle.clone()).unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::ArbitratorNotDrawn { .. }
+    ));
+
+    suite.draw_arbitrator(trade_id, 1000).unwrap();
+    let trade = suite.trade(trade_id);
+    assert_eq!(trade.arbitrator, Some(next.clone()));
+    assert!(trade
+        .state_history
+        .iter()
+        .any(|item| item.state.eq(&TradeState::ArbitratorReassigned)));
+    assert!(suite.execute(&previous, settle.clone()).is_err());
+
+    suite.execute(&next, settle).unwrap();
+    assert_eq!(suite.trade(trade_id).state, TradeState::SettledForMaker);
+    let maker_balance = INITIAL_BALANCE - 3 * PROTOCOL_FEE - ARBITRATION_FEE;
+    assert_eq!(suite.balance(&winner), maker_balance);
+    assert_eq!(suite.balance(&next), ARBITRATION_FEE);
+    assert_eq!(suite.balance(&previous), 0);
+    assert_eq!(suite.balance(&suite.trade), 0);
+}
@@ -0,0 +1,57 @@
+use super::*;
+use localmoney_protocol::trade::TradeState;
+
+// The escrow holds the amount and the maker fees, the protocol and arbitration fees come first
+const SPLIT_AMOUNT: u128 = TRADE_AMOUNT - ARBITRATION_FEE;
+
+#[test]
+fn split_settlement_shares_the_escrow_after_fees() {
+    let mut suite = Suite::new();
+    let arbitrator = suite.add_arbitrator("arbitrator");
+    let trade_id = suite.disputed_trade();
+    let maker = suite.maker.clone();
+    let taker = suite.taker.clone();
+
+    // Only the drawn arbitrator can split the escrow
+    let split = |buyer_share| ExecuteMsg::SettleDisputeSplit {
+        trade_id,
+        buyer_share,
+        decision_hash: None,
+    };
+    let err = suite
+        .execute(&taker, split(Decimal::percent(40)))
+        .unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::Unauthorized { .. }
+    ));
+    let err = suite
+        .execute(&arbitrator, split(Decimal::percent(101)))
+        .unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::InvalidParameter { .. }
+    ));
+
+    suite
+        .execute(&arbitrator, split(Decimal::percent(40)))
+        .unwrap();
+    let trade = suite.trade(trade_id);
+    assert_eq!(trade.state, TradeState::SettledSplit);
+
+    let buyer_amount = SPLIT_AMOUNT * 40 / 100;
+    assert_eq!(suite.balance(&taker), INITIAL_BALANCE + buyer_amount);
+    assert_eq!(
+        suite.balance(&maker),
+        INITIAL_BALANCE - TRADE_AMOUNT - 3 * PROTOCOL_FEE + SPLIT_AMOUNT - buyer_amount
+    );
+    assert_eq!(suite.balance(&arbitrator), ARBITRATION_FEE);
+    assert_eq!(suite.balance(&suite.warchest), PROTOCOL_FEE);
+    assert_eq!(suite.balance(&suite.chain_fee_collector), PROTOCOL_FEE);
+    assert_eq!(suite.balance(&suite.trade), 0);
+
+    // The dispute can only be settled once
+    assert!(suite
+        .execute(&arbitrator, split(Decimal::percent(40)))
+        .is_err());
+}
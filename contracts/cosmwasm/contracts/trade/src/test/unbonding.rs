@@ -0,0 +1,99 @@
+use super::*;
+use localmoney_protocol::trade::TradeState;
+
+const ARBITRATOR_BOND: u128 = 2_000_000;
+const UNBONDING_PERIOD: u64 = 1_000;
+
+fn unbonding_suite() -> Suite {
+    Suite::with_config(|config| {
+        config.arbitrator_bond = Uint128::new(ARBITRATOR_BOND);
+        config.arbitrator_unbonding_period = UNBONDING_PERIOD;
+    })
+}
+
+#[test]
+fn bond_is_claimable_after_the_unbonding_period() {
+    let mut suite = unbonding_suite();
+    let arbitrator = suite.bonded_arbitrator("arbitrator", ARBITRATOR_BOND);
+    assert_eq!(suite.balance(&arbitrator), 0);
+    assert_eq!(suite.balance(&suite.trade), ARBITRATOR_BOND);
+
+    // The bond can't be claimed without unbonding first
+    let claim = ExecuteMsg::ClaimArbitratorBond {};
+    let err = suite.execute(&arbitrator, claim.clone()).unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::InvalidParameter { .. }
+    ));
+
+    suite
+        .execute(&arbitrator, ExecuteMsg::UnbondArbitrator {})
+        .unwrap();
+    let bond = suite.arbitrator_bond(&arbitrator).unwrap();
+    assert_eq!(bond.unbonding_since, Some(START_TIME));
+
+    suite.set_time(START_TIME + UNBONDING_PERIOD - 1);
+    let err = suite.execute(&arbitrator, claim.clone()).unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::ArbitratorBondLocked { unlocks_at } if unlocks_at == START_TIME + UNBONDING_PERIOD
+    ));
+
+    suite.set_time(START_TIME + UNBONDING_PERIOD);
+    suite.execute(&arbitrator, claim.clone()).unwrap();
+    assert_eq!(suite.balance(&arbitrator), ARBITRATOR_BOND);
+    assert_eq!(suite.balance(&suite.trade), 0);
+    assert!(suite.arbitrator_bond(&arbitrator).is_none());
+    assert!(suite.execute(&arbitrator, claim).is_err());
+}
+
+#[test]
+fn unbonding_period_runs_past_the_last_open_dispute() {
+    let mut suite = unbonding_suite();
+    let arbitrator = suite.bonded_arbitrator("arbitrator", ARBITRATOR_BOND);
+    let trade_id = suite.disputed_trade();
+    assert_eq!(suite.trade(trade_id).arbitrator, Some(arbitrator.clone()));
+
+    // Unbonding doesn't take the arbitrator off its open dispute
+    suite
+        .execute(&arbitrator, ExecuteMsg::UnbondArbitrator {})
+        .unwrap();
+    let claim = ExecuteMsg::ClaimArbitratorBond {};
+    let err = suite.execute(&arbitrator, claim.clone()).unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::ArbitratorHasOpenDisputes { open_disputes: 1 }
+    ));
+
+    let settled_at = ROUND_2_TIME + PHASE_TIMER / 2;
+    suite.set_time(settled_at);
+    let taker = suite.taker.clone();
+    suite
+        .execute(
+            &arbitrator,
+            ExecuteMsg::SettleDispute {
+                trade_id,
+                winner: taker.clone(),
+                decision_hash: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(suite.trade(trade_id).state, TradeState::SettledForTaker);
+    assert_eq!(suite.balance(&arbitrator), ARBITRATION_FEE);
+
+    // The period started over when the dispute was settled
+    suite.set_time(ROUND_2_TIME + UNBONDING_PERIOD);
+    let err = suite.execute(&arbitrator, claim.clone()).unwrap_err();
+    assert!(matches!(
+        contract_error(err),
+        ContractError::ArbitratorBondLocked { unlocks_at } if unlocks_at == settled_at + UNBONDING_PERIOD
+    ));
+
+    suite.set_time(settled_at + UNBONDING_PERIOD);
+    suite.execute(&arbitrator, claim).unwrap();
+    assert_eq!(
+        suite.balance(&arbitrator),
+        ARBITRATION_FEE + ARBITRATOR_BOND
+    );
+    assert_eq!(suite.balance(&suite.trade), 0);
+}
@@ -0,0 +1,113 @@
+use cosmwasm_std::{to_json_vec, Addr, Api, Binary, StdError, StdResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::currencies::FiatCurrency;
+
+/// A trusted off-chain verifier of fiat payments, like a payment provider webhook or an
+/// open banking check, that signs a `PaymentAttestation` once it saw the buyer's payment.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Attestor {
+    pub attestor: Addr,
+    pub public_key: Binary, // secp256k1, compressed or uncompressed
+    pub description: String,
+    pub registered_at: u64,
+}
+
+/// The payload signed by an attestor, binding the attestation to a single trade.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PaymentAttestation {
+    pub chain_id: String,
+    pub trade_contract: Addr,
+    pub trade_id: u64,
+    pub buyer: Addr,
+    pub seller: Addr,
+    pub fiat: FiatCurrency,
+}
+
+impl PaymentAttestation {
+    /// The sha256 of the JSON encoded attestation, this is what the attestor signs.
+    pub fn digest(&self) -> StdResult<[u8; 32]> {
+        Ok(Sha256::digest(to_json_vec(self)?).into())
+    }
+
+    pub fn verify(&self, api: &dyn Api, signature: &[u8], public_key: &[u8]) -> StdResult<bool> {
+        api.secp256k1_verify(&self.digest()?, signature, public_key)
+            .map_err(|e| StdError::generic_err(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockApi;
+    use cosmwasm_std::HexBinary;
+
+    // secp256k1 key with the secret sha256("attestor")
+    const ATTESTOR_PUBLIC_KEY: &str =
+        "03d14ba672fc159e7fbe008b3eadf3257b97467eaf2f47e350c499efd6ffe0e570";
+    // secp256k1 key with the secret sha256("other")
+    const OTHER_PUBLIC_KEY: &str =
+        "0353fd1af2ef9cd4fe2b96ed6bd67a97d8830984bd0769be74209387bf7c9cf5d2";
+    const ATTESTATION_SIGNATURE: &str = concat!(
+        "6d4a073938cef1245e3b3ce41cd2f06dc73c351c18ff7b63bb5dc9a5b45ed496",
+        "6865a7d17fdf50bf29ce21cbcf11dc9a7bc75f1ae5e732910b35474ef736c493",
+    );
+
+    fn attestation() -> PaymentAttestation {
+        PaymentAttestation {
+            chain_id: "localmoney-1".to_string(),
+            trade_contract: Addr::unchecked("trade"),
+            trade_id: 1,
+            buyer: Addr::unchecked("buyer"),
+            seller: Addr::unchecked("seller"),
+            fiat: FiatCurrency::USD,
+        }
+    }
+
+    fn hex(value: &str) -> Vec<u8> {
+        HexBinary::from_hex(value).unwrap().to_vec()
+    }
+
+    #[test]
+    fn digests_the_json_encoded_attestation() {
+        // sha256 of {"chain_id":"localmoney-1","trade_contract":"trade","trade_id":1,
+        // "buyer":"buyer","seller":"seller","fiat":"USD"}
+        assert_eq!(
+            HexBinary::from(attestation().digest().unwrap().as_slice()).to_hex(),
+            "98dbf4f92d0791c40a7f6b1d6ed7a9b5d1d521b3f218f539e895a7e83f3d847e"
+        );
+    }
+
+    #[test]
+    fn verifies_attestor_signature() {
+        let api = MockApi::default();
+        let signature = hex(ATTESTATION_SIGNATURE);
+        assert!(attestation().verify(&api, &signature, &hex(ATTESTOR_PUBLIC_KEY)).unwrap());
+    }
+
+    #[test]
+    fn rejects_signature_of_another_trade() {
+        let api = MockApi::default();
+        let signature = hex(ATTESTATION_SIGNATURE);
+        let mut attestation = attestation();
+        attestation.trade_id = 2;
+        assert!(!attestation.verify(&api, &signature, &hex(ATTESTOR_PUBLIC_KEY)).unwrap());
+    }
+
+    #[test]
+    fn rejects_signature_from_another_attestor() {
+        let api = MockApi::default();
+        let signature = hex(ATTESTATION_SIGNATURE);
+        assert!(!attestation().verify(&api, &signature, &hex(OTHER_PUBLIC_KEY)).unwrap());
+    }
+
+    #[test]
+    fn fails_on_malformed_public_key() {
+        let api = MockApi::default();
+        let signature = hex(ATTESTATION_SIGNATURE);
+        let public_key = hex(ATTESTOR_PUBLIC_KEY)[..32].to_vec();
+        assert!(attestation().verify(&api, &signature, &public_key).is_err());
+    }
+}
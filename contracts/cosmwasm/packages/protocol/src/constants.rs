@@ -1,7 +1,25 @@
 pub const BASE_ORACLE_DENOM: &str = "ATOM";
 pub const OFFER_DESCRIPTION_LIMIT: usize = 140;
+pub const OFFER_LOCATION_MAX_PRECISION: usize = 5; // geohash of ~5km or a region code
+pub const OFFER_SCHEDULE_MAX_WINDOWS: usize = 28;
+pub const SECONDS_PER_WEEK: u64 = 604800;
+pub const OFFER_STATS_RELEASE_SAMPLES: usize = 50; // release times kept for the median
 pub const MAX_PLATFORM_FEE: u64 = 10; // 10%
 pub const MAX_TRADE_EXPIRATION_TIMER: u64 = 172800; // 2 days
 pub const MAX_TRADE_DISPUTE_TIMER: u64 = 86400; // 1 day
+pub const MAX_DISPUTE_RESOLUTION_TIMER: u64 = 1209600; // 14 days
+pub const MAX_ARBITRATION_PANEL_SIZE: u8 = 7;
+pub const MAX_APPEAL_WINDOW: u64 = 604800; // 7 days
+pub const MAX_DISPUTE_EVIDENCE_ITEMS: u32 = 50; // entries per author in the evidence log of a trade
+pub const EVIDENCE_URI_LIMIT: usize = 256;
+pub const EVIDENCE_DESCRIPTION_LIMIT: usize = 280;
+pub const MAX_ARBITRATOR_CONFLICTS: usize = 20;
+pub const MAX_ATTESTORS: usize = 20; // every attestor is tried when verifying a signature
+pub const ATTESTOR_DESCRIPTION_LIMIT: usize = 140;
+pub const MAX_ARBITRATOR_APPLICATION_ITEMS: usize = 10; // fiats or languages per application
+pub const MAX_ARBITRATOR_UNBONDING_PERIOD: u64 = 2419200; // 28 days
+pub const ARBITRATOR_WEIGHT_PRECISION: u64 = 1000; // keeps the load division exact enough
 pub const MIN_ITEMS_PER_PAGE: u32 = 1; // Pagination
 pub const MAX_ITEMS_PER_PAGE: u32 = 30; // Pagination
+pub const MAX_BATCH_QUERY_ITEMS: usize = 90; // the buyer, seller and arbitrator of a trades page
+pub const MAX_SCANNED_TRADES: usize = 300; // trades read by a page of the filtered trades query
@@ -1,3 +1,4 @@
+use crate::currencies::FiatCurrency;
 use crate::offer::OfferState;
 use crate::trade::TradeState;
 use cosmwasm_std::{Addr, StdError, Uint128, Uint256, Uint64};
@@ -40,6 +41,10 @@ pub enum ContractError {
     },
     #[error("Offer not found.")]
     OfferNotFound { offer_id: String },
+    #[error("Offer has expired. Expired at: {expired_at:?}.")]
+    OfferExpired { offer_id: u64, expired_at: u64 },
+    #[error("Offer is outside of its trading hours.")]
+    OfferClosed { offer_id: u64 },
     #[error("Value out of range.")]
     ValueOutOfRange {
         value: usize,
@@ -81,6 +86,51 @@ pub enum ContractError {
     InvalidTradeStateChange { from: TradeState, to: TradeState },
     #[error("Refund error: Not Expired")]
     RefundErrorNotExpired { message: String, trade: String },
+    #[error("The cancellation must be proposed by the counterparty first.")]
+    CancelNotProposed { trade_id: u64 },
+    #[error("The dispute can't be reassigned yet. Deadline: {dispute_deadline:?}.")]
+    DisputeDeadlineNotReached { dispute_deadline: u64 },
+    #[error("This dispute is settled by the votes of its arbitration panel.")]
+    DisputeHasPanel { trade_id: u64 },
+    #[error("The arbitrator already voted on this dispute.")]
+    DisputeAlreadyVoted { arbitrator: Addr },
+    #[error("The arbitrator of this dispute wasn't drawn yet.")]
+    ArbitratorNotDrawn { trade_id: u64 },
+    #[error("The arbitrators of this dispute were already drawn.")]
+    ArbitratorAlreadyDrawn { trade_id: u64 },
+    #[error("Invalid randomness beacon signature for round {round:?}.")]
+    InvalidBeaconSignature { round: u64 },
+    #[error("Arbitrators can't be drawn without a randomness beacon.")]
+    RandomnessBeaconNotConfigured {},
+    #[error("The appeal window closed at {appeal_deadline:?}.")]
+    AppealWindowClosed { appeal_deadline: u64 },
+    #[error("The decision can still be appealed until {appeal_deadline:?}.")]
+    AppealWindowOpen { appeal_deadline: u64 },
+    #[error(
+        "Insufficient appeal bond. Required: {required_amount:?}, Sent: {sent_amount:?}."
+    )]
+    InsufficientAppealBond {
+        required_amount: Uint128,
+        sent_amount: Uint128,
+    },
+    #[error("The sender can't add more entries to the evidence log of this dispute. Limit: {limit:?}.")]
+    DisputeEvidenceLimitReached { limit: u32 },
+    #[error("The offer of this trade doesn't accept payment attestations.")]
+    PaymentAttestationDisabled { trade_id: u64 },
+    #[error("The seller can still release the escrow until {releasable_at:?}.")]
+    PaymentAttestationTooEarly { releasable_at: u64 },
+    #[error("The payment attestation isn't signed by a registered attestor.")]
+    InvalidPaymentAttestation { trade_id: u64 },
+    #[error("Attestors limit reached. Limit: {limit:?}.")]
+    AttestorsLimitReached { limit: usize },
+    #[error("The relayed action expired at {deadline:?}.")]
+    RelayDeadlinePassed { deadline: u64 },
+    #[error("The relayed action isn't signed by the given public key.")]
+    InvalidRelaySignature {},
+    #[error("Invalid relay nonce. Expected: {expected:?}, Received: {received:?}.")]
+    InvalidRelayNonce { expected: u64, received: u64 },
+    #[error("No arbitrator available for {fiat:?}.")]
+    NoArbitratorAvailable { fiat: FiatCurrency },
     #[error("This trade has expired.")]
     TradeExpired { expired_at: u64, created_at: u64 },
     #[error("Swap Error: received amount is less than expected.")]
@@ -89,6 +139,26 @@ pub enum ContractError {
     SwapErrorMissingDenom { expected_denom: String },
     #[error("Unknown reply id: {reply_id:?}")]
     UnknownReplyId { reply_id: u64 },
+    /// Arbitrator Errors
+    #[error(
+        "Insufficient arbitrator bond. Required: {required_amount:?}, Sent: {sent_amount:?}."
+    )]
+    InsufficientArbitratorBond {
+        required_amount: Uint128,
+        sent_amount: Uint128,
+    },
+    #[error("An application of {applicant:?} is already pending.")]
+    ArbitratorApplicationPending { applicant: Addr },
+    #[error("No pending application for {applicant:?}.")]
+    ArbitratorApplicationNotFound { applicant: Addr },
+    #[error("{arbitrator:?} isn't an arbitrator.")]
+    ArbitratorNotFound { arbitrator: Addr },
+    #[error("No bond for arbitrator {arbitrator:?}.")]
+    ArbitratorBondNotFound { arbitrator: Addr },
+    #[error("The arbitrator still has {open_disputes:?} open disputes.")]
+    ArbitratorHasOpenDisputes { open_disputes: u32 },
+    #[error("The arbitrator bond is locked until {unlocks_at:?}.")]
+    ArbitratorBondLocked { unlocks_at: u64 },
     /// Profile Errors
     #[error("Active offers limit reached. Limit: {limit:?}.")]
     ActiveOffersLimitReached { limit: u8 },
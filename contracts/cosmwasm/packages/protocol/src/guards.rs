@@ -1,8 +1,12 @@
-use crate::constants::{MAX_ITEMS_PER_PAGE, MIN_ITEMS_PER_PAGE, OFFER_DESCRIPTION_LIMIT};
+use crate::constants::{
+    MAX_BATCH_QUERY_ITEMS, MAX_ITEMS_PER_PAGE, MIN_ITEMS_PER_PAGE, OFFER_DESCRIPTION_LIMIT,
+    OFFER_LOCATION_MAX_PRECISION, OFFER_SCHEDULE_MAX_WINDOWS, SECONDS_PER_WEEK,
+};
 use crate::errors::ContractError;
-use crate::offer::OfferType;
-use crate::trade::{Trade, TradeState};
-use cosmwasm_std::{Addr, Uint128, Uint256};
+use crate::hub::HubConfig;
+use crate::offer::{OfferTimers, OfferType, PaymentType, TradingWindow};
+use crate::trade::{Trade, TradeState, TradeTimers};
+use cosmwasm_std::{Addr, StdError, StdResult, Uint128, Uint256};
 use cw2::ContractVersion;
 
 pub fn assert_multiple_ownership(caller: Addr, owners: Vec<Addr>) -> Result<(), ContractError> {
@@ -149,6 +153,138 @@ pub fn assert_offer_description_valid(description: Option<String>) -> Result<(),
     }
 }
 
+pub fn assert_offer_expiration_valid(
+    expires_at: Option<u64>,
+    block_time: u64,
+) -> Result<(), ContractError> {
+    match expires_at {
+        Some(expires_at) if expires_at <= block_time => Err(ContractError::InvalidParameter {
+            parameter: "expires_at".to_string(),
+            message: Some("The expiration must be in the future.".to_string()),
+        }),
+        _ => Ok(()),
+    }
+}
+
+// Cash offers must publish a coarse location, exact locations are rejected
+pub fn assert_offer_location_valid(
+    location: &Option<String>,
+    payment_type: &PaymentType,
+) -> Result<(), ContractError> {
+    match location {
+        None if payment_type.eq(&PaymentType::Cash) => Err(ContractError::InvalidParameter {
+            parameter: "location".to_string(),
+            message: Some("Cash offers must define a location.".to_string()),
+        }),
+        None => Ok(()),
+        Some(location) => assert_location_prefix_valid(location),
+    }
+}
+
+pub fn assert_location_prefix_valid(location: &str) -> Result<(), ContractError> {
+    if location.is_empty() || location.len() > OFFER_LOCATION_MAX_PRECISION {
+        let message = format!(
+            "The location must have between 1 and {OFFER_LOCATION_MAX_PRECISION} characters."
+        );
+        return Err(ContractError::InvalidParameter {
+            parameter: "location".to_string(),
+            message: Some(message),
+        });
+    }
+    if !location
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c.eq(&'-'))
+    {
+        return Err(ContractError::InvalidParameter {
+            parameter: "location".to_string(),
+            message: Some("Only lowercase geohash or region code characters are allowed.".to_string()),
+        });
+    }
+    Ok(())
+}
+
+pub fn assert_offer_schedule_valid(
+    schedule: &Option<Vec<TradingWindow>>,
+) -> Result<(), ContractError> {
+    let windows = match schedule {
+        None => return Ok(()),
+        Some(windows) => windows,
+    };
+    if windows.is_empty() || windows.len() > OFFER_SCHEDULE_MAX_WINDOWS {
+        let message =
+            format!("The schedule must have between 1 and {OFFER_SCHEDULE_MAX_WINDOWS} windows.");
+        return Err(ContractError::InvalidParameter {
+            parameter: "schedule".to_string(),
+            message: Some(message),
+        });
+    }
+    if windows
+        .iter()
+        .any(|window| window.start >= window.end || window.end > SECONDS_PER_WEEK)
+    {
+        let message = format!(
+            "Each window must start before it ends and end before {SECONDS_PER_WEEK} seconds."
+        );
+        return Err(ContractError::InvalidParameter {
+            parameter: "schedule".to_string(),
+            message: Some(message),
+        });
+    }
+    Ok(())
+}
+
+// Offers can shorten the hub phase timers but never extend them
+pub fn assert_offer_timers_valid(
+    timers: &Option<OfferTimers>,
+    hub_config: &HubConfig,
+) -> Result<(), ContractError> {
+    let timers = match timers {
+        None => return Ok(()),
+        Some(timers) => timers,
+    };
+    let hub_timers = TradeTimers::new(hub_config, &None);
+    let phases = [
+        ("accept", timers.accept, hub_timers.accept),
+        ("funding", timers.funding, hub_timers.funding),
+        ("payment", timers.payment, hub_timers.payment),
+        ("release", timers.release, hub_timers.release),
+    ];
+    for (phase, timer, max_timer) in phases {
+        if timer.is_some_and(|timer| timer.eq(&0) || timer > max_timer) {
+            return Err(ContractError::InvalidParameter {
+                parameter: format!("timers.{phase}"),
+                message: Some(format!("Must be between 1 and {max_timer} seconds.")),
+            });
+        }
+    }
+    Ok(())
+}
+
+// Evidence and decisions are committed on-chain as the hex encoded sha256 of their content
+pub fn assert_hash_valid(parameter: &str, hash: &str) -> Result<(), ContractError> {
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ContractError::InvalidParameter {
+            parameter: parameter.to_string(),
+            message: Some("Must be a hex encoded sha256 hash.".to_string()),
+        });
+    }
+    Ok(())
+}
+
+pub fn assert_text_length_valid(
+    parameter: &str,
+    text: &str,
+    limit: usize,
+) -> Result<(), ContractError> {
+    if text.is_empty() || text.len() > limit {
+        return Err(ContractError::InvalidParameter {
+            parameter: parameter.to_string(),
+            message: Some(format!("Must be between 1 and {limit} characters.")),
+        });
+    }
+    Ok(())
+}
+
 pub fn assert_migration_parameters(
     previous_contract_version: ContractVersion,
     contract_name: String,
@@ -179,3 +315,12 @@ pub fn assert_migration_parameters(
 pub fn validate_min_max_items_per_page(limit: u32) -> u32 {
     limit.max(MIN_ITEMS_PER_PAGE).min(MAX_ITEMS_PER_PAGE)
 }
+
+pub fn validate_batch_query_size(items: usize) -> StdResult<()> {
+    if items > MAX_BATCH_QUERY_ITEMS {
+        return Err(StdError::generic_err(format!(
+            "Up to {MAX_BATCH_QUERY_ITEMS} items can be queried at once."
+        )));
+    }
+    Ok(())
+}
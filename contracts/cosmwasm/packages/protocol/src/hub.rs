@@ -1,3 +1,4 @@
+use crate::randomness::RandomnessBeacon;
 use cosmwasm_std::{Addr, Decimal, Uint128};
 use cw20::Denom;
 use schemars::JsonSchema;
@@ -49,8 +50,45 @@ pub struct HubConfig {
     pub trade_dispute_timer: u64,
     pub trade_limit_min: Uint128, // in USD
     pub trade_limit_max: Uint128, // in USD
+    #[serde(default)]
+    pub maker_presence_window: u64, // in seconds, 0 disables the presence filter
+    #[serde(default)]
+    pub expiration_bounty_pct: Decimal, // share of the warchest fee paid to keepers expiring trades
+    // Phase timers in seconds, 0 falls back to trade_expiration_timer
+    #[serde(default)]
+    pub trade_accept_timer: u64,
+    #[serde(default)]
+    pub trade_funding_timer: u64,
+    #[serde(default)]
+    pub trade_payment_timer: u64,
+    // in seconds, 0 falls back to trade_dispute_timer
+    #[serde(default)]
+    pub trade_release_timer: u64,
+    #[serde(default)]
+    pub dispute_resolution_timer: u64, // in seconds, 0 disables arbitrator reassignment
+    #[serde(default)]
+    pub arbitration_panel_threshold: Uint128, // in USD, 0 disables arbitration panels
+    #[serde(default)]
+    pub arbitration_panel_size: u8, // odd number of arbitrators voting on a panel dispute
+    pub randomness_beacon: Option<RandomnessBeacon>, // draws arbitrators, required for disputes
+    #[serde(default)]
+    pub arbitrator_bond: Uint128, // in local_denom, required to apply as an arbitrator
+    #[serde(default)]
+    pub arbitrator_unbonding_period: u64, // in seconds, counted from the last open dispute
+    #[serde(default)]
+    pub appeal_threshold: Uint128, // in USD, 0 disables appeals
+    #[serde(default)]
+    pub appeal_window: u64, // in seconds the losing party has to appeal a decision
+    #[serde(default)]
+    pub appeal_bond_pct: Decimal, // of the trade amount, posted by the appellant
+    #[serde(default)]
+    pub overturned_decision_slash: Uint128, // in local_denom, per arbitrator of a reversed decision
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    // Disputes can't be opened without a beacon, hubs configured before it was required need one
+    #[serde(default)]
+    pub randomness_beacon: Option<RandomnessBeacon>,
+}
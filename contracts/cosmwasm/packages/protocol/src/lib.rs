@@ -1,3 +1,4 @@
+pub mod attestation;
 pub mod constants;
 pub mod currencies;
 pub mod denom_utils;
@@ -8,4 +9,6 @@ pub mod hub_utils;
 pub mod offer;
 pub mod price;
 pub mod profile;
+pub mod randomness;
+pub mod relay;
 pub mod trade;
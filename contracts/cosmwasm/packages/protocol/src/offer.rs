@@ -1,12 +1,18 @@
+use crate::constants::{
+    ARBITRATOR_WEIGHT_PRECISION, OFFER_STATS_RELEASE_SAMPLES, SECONDS_PER_WEEK,
+};
 use crate::currencies::FiatCurrency;
 use crate::denom_utils::denom_to_string;
 use crate::guards::validate_min_max_items_per_page;
 use crate::hub_utils::get_hub_config;
-use crate::profile::{load_profile, load_profiles, Profile};
+use crate::profile::{load_profile, load_profiles_by_addrs, Profile};
 use crate::trade::{TradeResponse, TradeState};
-use cosmwasm_std::{Addr, CustomQuery, Deps, Order, QuerierWrapper, StdResult, Storage, Uint128};
+use cosmwasm_std::{
+    to_json_binary, Addr, CosmosMsg, CustomQuery, Deps, Order, QuerierWrapper, StdResult, Storage,
+    SubMsg, Uint128, WasmMsg,
+};
 use cw20::Denom;
-use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, MultiIndex};
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Map, MultiIndex};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self};
@@ -14,6 +20,19 @@ use std::fmt::{self};
 
 pub static CONFIG_KEY: &[u8] = b"config";
 
+// Every prefix of an offer's location points to the offer id, so a location prefix
+// query is a plain storage prefix scan.
+pub const OFFER_LOCATIONS: Map<(&str, u64), u64> = Map::new("offer_locations");
+
+pub const OFFER_STATS: Map<u64, OfferStats> = Map::new("offer_stats");
+// Most recent release times of an offer, oldest first, only used to compute its median
+pub const OFFER_RELEASE_TIMES: Map<u64, Vec<u64>> = Map::new("offer_release_times");
+
+// Offer counters maintained on every state transition, keyed by state and by (fiat, state)
+pub const OFFERS_COUNT_BY_STATE: Map<&str, u64> = Map::new("offers_count_by_state");
+pub const OFFERS_COUNT_BY_FIAT_STATE: Map<(&str, &str), FiatOffersCount> =
+    Map::new("offers_count_by_fiat_state");
+
 pub struct OfferIndexes<'a> {
     // pk goes to second tuple element
     pub owner: MultiIndex<'a, Addr, Offer, u64>,
@@ -68,6 +87,14 @@ pub struct OfferMsg {
     pub min_amount: Uint128,
     pub max_amount: Uint128,
     pub description: Option<String>,
+    pub expires_at: Option<u64>,
+    #[serde(default)]
+    pub payment_type: PaymentType,
+    pub location: Option<String>,
+    pub schedule: Option<Vec<TradingWindow>>,
+    pub timers: Option<OfferTimers>,
+    #[serde(default)]
+    pub payment_attestation: bool, // the buyer can release with a registered attestor's proof
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -80,6 +107,12 @@ pub struct OfferUpdateMsg {
     pub max_amount: Uint128,
     pub state: OfferState,
     pub description: Option<String>,
+    pub expires_at: Option<u64>,
+    pub location: Option<String>,
+    pub schedule: Option<Vec<TradingWindow>>,
+    pub timers: Option<OfferTimers>,
+    #[serde(default)]
+    pub payment_attestation: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -89,6 +122,16 @@ pub enum ExecuteMsg {
     Create { offer: OfferMsg },
     UpdateOffer { offer_update: OfferUpdateMsg },
     RegisterHub {},
+    PauseStaleOffers {
+        start_after: Option<u64>,
+        limit: u32,
+    },
+    UpdateTradeStats {
+        offer_id: u64,
+        trade_state: TradeState,
+        amount: Uint128,
+        release_time: Option<u64>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -105,12 +148,21 @@ pub enum QueryMsg {
         order: OfferOrder,
         limit: u32,
         last: Option<u64>,
+        exclude_closed: Option<bool>,
     },
     OffersByOwner {
         owner: Addr,
         limit: u32,
         last: Option<u64>,
     },
+    OffersByIds {
+        ids: Vec<u64>,
+    },
+    OffersByLocation {
+        location: String,
+        limit: u32,
+        last: Option<u64>,
+    },
     OffersCountByStates {
         states: Vec<OfferState>,
     },
@@ -143,12 +195,134 @@ pub struct Offer {
     pub denom: Denom,
     pub state: OfferState,
     pub timestamp: u64,
+    pub expires_at: Option<u64>,
+    #[serde(default)]
+    pub payment_type: PaymentType,
+    pub location: Option<String>,
+    pub schedule: Option<Vec<TradingWindow>>,
+    pub timers: Option<OfferTimers>,
+    #[serde(default)]
+    pub payment_attestation: bool,
+}
+
+impl Offer {
+    pub fn is_expired(&self, block_time: u64) -> bool {
+        self.expires_at
+            .map(|expires_at| block_time > expires_at)
+            .unwrap_or(false)
+    }
+
+    // Offers without a schedule are always open
+    pub fn is_open(&self, block_time: u64) -> bool {
+        match &self.schedule {
+            None => true,
+            Some(windows) => {
+                let time_of_week = seconds_of_week(block_time);
+                windows
+                    .iter()
+                    .any(|window| window.start <= time_of_week && time_of_week < window.end)
+            }
+        }
+    }
+}
+
+/// Per offer overrides of the hub phase timers, in seconds. They can only shorten them.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct OfferTimers {
+    pub accept: Option<u64>,
+    pub funding: Option<u64>,
+    pub payment: Option<u64>,
+    pub release: Option<u64>,
+}
+
+/// A weekly availability window, in seconds since Monday 00:00 UTC.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TradingWindow {
+    pub start: u64,
+    pub end: u64,
+}
+
+// The unix epoch was a Thursday, so we shift it by 3 days to start the week on Monday
+pub fn seconds_of_week(block_time: u64) -> u64 {
+    (block_time + 3 * 86400) % SECONDS_PER_WEEK
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct OfferStats {
+    pub requested_trades_count: u64,
+    pub completed_trades_count: u64,
+    pub canceled_trades_count: u64,
+    pub disputed_trades_count: u64,
+    pub total_volume: Uint128,
+    pub median_release_time: u64, // in seconds
+    #[serde(default)]
+    pub expired_trades_count: u64, // requests that expired before being funded
+    #[serde(default)]
+    pub refunded_trades_count: u64, // funded escrows refunded after expiring
+    #[serde(default)]
+    pub partially_released_trades_count: u64,
+    #[serde(default)]
+    pub split_trades_count: u64, // disputes settled by splitting the escrow
+}
+
+impl OfferStats {
+    pub fn register_trade(
+        &mut self,
+        release_times: &mut Vec<u64>,
+        trade_state: &TradeState,
+        amount: Uint128,
+        release_time: Option<u64>,
+    ) {
+        match trade_state {
+            TradeState::RequestCreated => self.requested_trades_count += 1,
+            TradeState::RequestCanceled | TradeState::EscrowCanceled => {
+                self.canceled_trades_count += 1
+            }
+            TradeState::EscrowDisputed => self.disputed_trades_count += 1,
+            TradeState::RequestExpired => self.expired_trades_count += 1,
+            TradeState::EscrowRefunded => self.refunded_trades_count += 1,
+            TradeState::EscrowReleased => {
+                self.completed_trades_count += 1;
+                self.total_volume += amount;
+                if let Some(release_time) = release_time {
+                    self.add_release_time(release_times, release_time);
+                }
+            }
+            // Only the released part of the escrow counts towards the volume
+            TradeState::EscrowPartiallyReleased => {
+                self.partially_released_trades_count += 1;
+                self.total_volume += amount;
+                if let Some(release_time) = release_time {
+                    self.add_release_time(release_times, release_time);
+                }
+            }
+            TradeState::SettledSplit => self.split_trades_count += 1,
+            _ => {}
+        }
+    }
+
+    fn add_release_time(&mut self, release_times: &mut Vec<u64>, release_time: u64) {
+        release_times.push(release_time);
+        if release_times.len() > OFFER_STATS_RELEASE_SAMPLES {
+            release_times.remove(0);
+        }
+        let mut sorted = release_times.clone();
+        sorted.sort_unstable();
+        let middle = sorted.len() / 2;
+        self.median_release_time = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct OfferResponse {
     pub offer: Offer,
     pub profile: Profile,
+    #[serde(default)]
+    pub stats: OfferStats,
 }
 
 pub struct OfferModel<'a> {
@@ -158,23 +332,171 @@ pub struct OfferModel<'a> {
 
 impl<'a> OfferModel<'a> {
     pub fn store(storage: &mut dyn Storage, offer: &Offer) -> StdResult<()> {
+        let previous_state = offers().may_load(storage, offer.id)?.map(|o| o.state);
+        if previous_state.as_ref().ne(&Some(&offer.state)) {
+            if let Some(previous_state) = previous_state {
+                OfferModel::decrement_count(storage, &offer.fiat_currency, &previous_state)?;
+            }
+            OfferModel::increment_count(storage, &offer.fiat_currency, &offer.state)?;
+        }
         offers().save(storage, offer.id, offer)
     }
 
+    fn increment_count(
+        storage: &mut dyn Storage,
+        fiat: &FiatCurrency,
+        state: &OfferState,
+    ) -> StdResult<()> {
+        let state_key = state.to_string();
+        let fiat_key = fiat.to_string();
+        OFFERS_COUNT_BY_STATE.update(storage, &state_key, |count| -> StdResult<u64> {
+            Ok(count.unwrap_or_default() + 1)
+        })?;
+        OFFERS_COUNT_BY_FIAT_STATE.update(
+            storage,
+            (&fiat_key, &state_key),
+            |fiat_count| -> StdResult<FiatOffersCount> {
+                let mut fiat_count = fiat_count.unwrap_or(FiatOffersCount {
+                    fiat: fiat.clone(),
+                    count: 0,
+                });
+                fiat_count.count += 1;
+                Ok(fiat_count)
+            },
+        )?;
+        Ok(())
+    }
+
+    fn decrement_count(
+        storage: &mut dyn Storage,
+        fiat: &FiatCurrency,
+        state: &OfferState,
+    ) -> StdResult<()> {
+        let state_key = state.to_string();
+        let fiat_key = fiat.to_string();
+        OFFERS_COUNT_BY_STATE.update(storage, &state_key, |count| -> StdResult<u64> {
+            Ok(count.unwrap_or_default().saturating_sub(1))
+        })?;
+        OFFERS_COUNT_BY_FIAT_STATE.update(
+            storage,
+            (&fiat_key, &state_key),
+            |fiat_count| -> StdResult<FiatOffersCount> {
+                let mut fiat_count = fiat_count.unwrap_or(FiatOffersCount {
+                    fiat: fiat.clone(),
+                    count: 0,
+                });
+                fiat_count.count = fiat_count.count.saturating_sub(1);
+                Ok(fiat_count)
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Recounts the state counters from the stored offers, used by migrations.
+    pub fn backfill_counts(storage: &mut dyn Storage) -> StdResult<()> {
+        let all_offers: Vec<Offer> = offers()
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .map(|(_, offer)| offer)
+            .collect();
+        OFFERS_COUNT_BY_STATE.clear(storage);
+        OFFERS_COUNT_BY_FIAT_STATE.clear(storage);
+        for offer in all_offers {
+            OfferModel::increment_count(storage, &offer.fiat_currency, &offer.state)?;
+        }
+        Ok(())
+    }
+
+    /// Moves the release time samples out of the stats returned with every offer,
+    /// used by migrations.
+    pub fn backfill_release_times(storage: &mut dyn Storage) -> StdResult<()> {
+        #[derive(Serialize, Deserialize)]
+        struct LegacyOfferStats {
+            #[serde(default)]
+            release_times: Vec<u64>,
+        }
+        const LEGACY_OFFER_STATS: Map<u64, LegacyOfferStats> = Map::new("offer_stats");
+
+        let legacy_stats: Vec<(u64, LegacyOfferStats)> = LEGACY_OFFER_STATS
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (offer_id, legacy) in legacy_stats {
+            if !legacy.release_times.is_empty() {
+                OFFER_RELEASE_TIMES.save(storage, offer_id, &legacy.release_times)?;
+            }
+            // Saving the stats again drops the samples from them
+            let stats = OfferModel::stats(storage, offer_id);
+            OFFER_STATS.save(storage, offer_id, &stats)?;
+        }
+        Ok(())
+    }
+
+    /// Registers a trade transition in the stats of its offer.
+    pub fn register_trade(
+        storage: &mut dyn Storage,
+        offer_id: u64,
+        trade_state: &TradeState,
+        amount: Uint128,
+        release_time: Option<u64>,
+    ) -> StdResult<OfferStats> {
+        let mut stats = OfferModel::stats(storage, offer_id);
+        let mut release_times = OFFER_RELEASE_TIMES
+            .may_load(storage, offer_id)?
+            .unwrap_or_default();
+        stats.register_trade(&mut release_times, trade_state, amount, release_time);
+        OFFER_STATS.save(storage, offer_id, &stats)?;
+        if release_time.is_some() {
+            OFFER_RELEASE_TIMES.save(storage, offer_id, &release_times)?;
+        }
+        Ok(stats)
+    }
+
+    pub fn set_state(&mut self, state: OfferState) -> &Offer {
+        self.offer.state = state;
+        OfferModel::store(self.storage, &self.offer).unwrap();
+        &self.offer
+    }
+
     pub fn from_store(storage: &dyn Storage, id: u64) -> Offer {
         offers().may_load(storage, id).unwrap_or_default().unwrap()
     }
 
     pub fn create(storage: &mut dyn Storage, offer: Offer) -> OfferModel {
         OfferModel::store(storage, &offer).unwrap();
+        OfferModel::store_location(storage, &offer).unwrap();
         OfferModel { offer, storage }
     }
 
+    fn store_location(storage: &mut dyn Storage, offer: &Offer) -> StdResult<()> {
+        if let Some(location) = &offer.location {
+            for end in 1..=location.len() {
+                OFFER_LOCATIONS.save(storage, (&location[..end], offer.id), &offer.id)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_location(storage: &mut dyn Storage, offer: &Offer) {
+        if let Some(location) = &offer.location {
+            for end in 1..=location.len() {
+                OFFER_LOCATIONS.remove(storage, (&location[..end], offer.id));
+            }
+        }
+    }
+
     pub fn save(self) -> Offer {
         OfferModel::store(self.storage, &self.offer).unwrap();
         self.offer
     }
 
+    pub fn stats(storage: &dyn Storage, id: u64) -> OfferStats {
+        OFFER_STATS
+            .may_load(storage, id)
+            .unwrap_or_default()
+            .unwrap_or_default()
+    }
+
     pub fn may_load(storage: &'a mut dyn Storage, id: u64) -> OfferModel<'a> {
         
         OfferModel {
@@ -189,6 +511,15 @@ impl<'a> OfferModel<'a> {
         self.offer.max_amount = msg.max_amount;
         self.offer.state = msg.state;
         self.offer.description = msg.description;
+        self.offer.expires_at = msg.expires_at;
+        self.offer.schedule = msg.schedule;
+        self.offer.timers = msg.timers;
+        self.offer.payment_attestation = msg.payment_attestation;
+        if self.offer.location.ne(&msg.location) {
+            OfferModel::remove_location(self.storage, &self.offer);
+            self.offer.location = msg.location;
+            OfferModel::store_location(self.storage, &self.offer).unwrap();
+        }
         OfferModel::store(self.storage, &self.offer).unwrap();
         &self.offer
     }
@@ -217,7 +548,12 @@ impl<'a> OfferModel<'a> {
                         offer.clone().owner,
                     )
                     .unwrap();
-                    OfferResponse { offer, profile }
+                    let stats = OfferModel::stats(deps.storage, offer.id);
+                    OfferResponse {
+                        offer,
+                        profile,
+                        stats,
+                    }
                 })
             })
             .collect();
@@ -225,6 +561,61 @@ impl<'a> OfferModel<'a> {
         Ok(result)
     }
 
+    pub fn query_by_location(
+        deps: Deps,
+        location: String,
+        limit: u32,
+        last: Option<u64>,
+        block_time: u64,
+    ) -> StdResult<Vec<OfferResponse>> {
+        let hub_config = get_hub_config(deps);
+        let range_from = last.map(Bound::exclusive);
+        let limit = validate_min_max_items_per_page(limit);
+
+        let mut offers = OFFER_LOCATIONS
+            .prefix(location.as_str())
+            .range(deps.storage, None, range_from, Order::Descending)
+            .flat_map(|item| item.map(|(id, _)| OfferModel::from_store(deps.storage, id)))
+            .filter(|offer| {
+                offer.state.eq(&OfferState::Active)
+                    && offer.payment_type.eq(&PaymentType::Cash)
+                    && !offer.is_expired(block_time)
+            });
+
+        // Owners are loaded one page at a time, like in `query_by`
+        let mut result: Vec<OfferResponse> = vec![];
+        while result.len() < limit as usize {
+            let chunk: Vec<Offer> = offers.by_ref().take(limit as usize).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            let owners: Vec<Addr> = chunk.iter().map(|offer| offer.owner.clone()).collect();
+            let profiles = load_profiles_by_addrs(
+                &deps.querier,
+                hub_config.profile_addr.to_string(),
+                owners,
+            )?;
+            for (offer, profile) in chunk.into_iter().zip(profiles) {
+                // Hide offers from makers that haven't sent a heartbeat within the presence window
+                if !profile.is_present(hub_config.maker_presence_window, block_time) {
+                    continue;
+                }
+                let stats = OfferModel::stats(deps.storage, offer.id);
+                result.push(OfferResponse {
+                    offer,
+                    profile,
+                    stats,
+                });
+                if result.len() == limit as usize {
+                    break;
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn query_by<T: CustomQuery>(
         deps: Deps<T>,
         offer_type: OfferType,
@@ -233,6 +624,8 @@ impl<'a> OfferModel<'a> {
         order: OfferOrder,
         limit: u32,
         last: Option<u64>,
+        exclude_closed: bool,
+        block_time: u64,
     ) -> StdResult<Vec<OfferResponse>> {
         let hub_config = get_hub_config(deps);
         let storage = deps.storage;
@@ -240,49 +633,51 @@ impl<'a> OfferModel<'a> {
         let range_from = last.map(Bound::exclusive);
         let limit = validate_min_max_items_per_page(limit);
 
-        let mut profiles = load_profiles(
-            &deps.querier,
-            hub_config.profile_addr.to_string(),
-            limit,
-            None,
-        )
-        .unwrap();
-
         let prefix = offer_type.to_string()
             + &fiat_currency.to_string()
             + &denom_to_string(&denom)
             + &*OfferState::Active.to_string();
 
-        let mut result: Vec<OfferResponse> = offers()
+        let mut offers = offers()
             .idx
             .filter
             .prefix(prefix)
             .range(storage, None, range_from, std_order)
-            .flat_map(|item| {
-                item.map(|(_, offer)| {
-                    let profile_found = profiles
-                        .clone()
-                        .into_iter()
-                        .find(|profile| profile.addr.eq(&offer.owner));
-
-                    let profile = if profile_found.is_some() {
-                        profile_found.unwrap()
-                    } else {
-                        let new_profile = load_profile(
-                            &deps.querier,
-                            hub_config.profile_addr.to_string(),
-                            offer.owner.clone(),
-                        )
-                        .unwrap();
-                        profiles.push(new_profile.clone());
-                        new_profile
-                    };
-
-                    OfferResponse { offer, profile }
-                })
-            })
-            .take(limit as usize)
-            .collect();
+            .flat_map(|item| item.map(|(_, offer)| offer))
+            // Expired offers are hidden until the keeper pauses them
+            .filter(|offer| !offer.is_expired(block_time))
+            .filter(|offer| !exclude_closed || offer.is_open(block_time));
+
+        // Owners are loaded one page at a time, so makers filtered out by the presence window
+        // are replaced by the next offers instead of shrinking the page.
+        let mut result: Vec<OfferResponse> = vec![];
+        while result.len() < limit as usize {
+            let chunk: Vec<Offer> = offers.by_ref().take(limit as usize).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            let owners: Vec<Addr> = chunk.iter().map(|offer| offer.owner.clone()).collect();
+            let profiles = load_profiles_by_addrs(
+                &deps.querier,
+                hub_config.profile_addr.to_string(),
+                owners,
+            )?;
+            for (offer, profile) in chunk.into_iter().zip(profiles) {
+                // Hide offers from makers that haven't sent a heartbeat within the presence window
+                if !profile.is_present(hub_config.maker_presence_window, block_time) {
+                    continue;
+                }
+                let stats = OfferModel::stats(storage, offer.id);
+                result.push(OfferResponse {
+                    offer,
+                    profile,
+                    stats,
+                });
+                if result.len() == limit as usize {
+                    break;
+                }
+            }
+        }
 
         match order {
             OfferOrder::TradesCount => {
@@ -321,6 +716,17 @@ pub struct TradeAddr {
 pub struct Arbitrator {
     pub arbitrator: Addr,
     pub fiat: FiatCurrency,
+    pub weight: Option<u32>, // relative chance of being drawn, 1 if unset
+    #[serde(default)]
+    pub languages: Vec<String>,
+}
+
+impl Arbitrator {
+    /// The configured weight divided by the disputes the arbitrator already has open.
+    pub fn selection_weight(&self, open_disputes: u32) -> u64 {
+        let weight = self.weight.unwrap_or(1) as u64;
+        weight * ARBITRATOR_WEIGHT_PRECISION / (open_disputes as u64 + 1)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -330,6 +736,14 @@ pub enum OfferType {
     Sell,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentType {
+    #[default]
+    Online,
+    Cash,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum OfferOrder {
@@ -359,6 +773,27 @@ pub enum OfferState {
 
 // Price
 
+// Execute Util
+pub fn update_offer_trade_stats_msg(
+    offer_contract: String,
+    offer_id: u64,
+    trade_state: TradeState,
+    amount: Uint128,
+    release_time: Option<u64>,
+) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: offer_contract,
+        msg: to_json_binary(&ExecuteMsg::UpdateTradeStats {
+            offer_id,
+            trade_state,
+            amount,
+            release_time,
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
 // Queries
 pub fn load_offer<T: CustomQuery>(
     querier: &QuerierWrapper<T>,
@@ -367,6 +802,13 @@ pub fn load_offer<T: CustomQuery>(
 ) -> StdResult<OfferResponse> {
     querier.query_wasm_smart(offer_contract, &QueryMsg::Offer { id: offer_id })
 }
+pub fn load_offers_by_ids<T: CustomQuery>(
+    querier: &QuerierWrapper<T>,
+    ids: Vec<u64>,
+    offer_contract: String,
+) -> StdResult<Vec<OfferResponse>> {
+    querier.query_wasm_smart(offer_contract, &QueryMsg::OffersByIds { ids })
+}
 // Migration
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -1,9 +1,10 @@
+use crate::guards::validate_min_max_items_per_page;
 use crate::{offer::OfferState, trade::TradeState};
 use cosmwasm_std::{
-    to_json_binary, Addr, CosmosMsg, CustomQuery, Deps, Env, Order, QuerierWrapper, StdResult,
-    Storage, SubMsg, WasmMsg,
+    to_json_binary, Addr, CosmosMsg, CustomQuery, Deps, Order, QuerierWrapper, StdResult, Storage,
+    SubMsg, WasmMsg,
 };
-use cw_storage_plus::{Index, IndexList, IndexedMap, MultiIndex};
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, MultiIndex};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -27,6 +28,7 @@ pub enum ExecuteMsg {
         profile_addr: Addr,
         trade_state: TradeState,
     },
+    Heartbeat {},
     RegisterHub {},
 }
 
@@ -34,7 +36,12 @@ pub enum ExecuteMsg {
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Profile { addr: Addr },
-    Profiles { limit: u32, start_at: Option<u64> },
+    Profiles {
+        start_after: Option<Addr>,
+        limit: u32,
+        order: Option<ProfileOrder>,
+    },
+    ProfilesByAddrs { addrs: Vec<Addr> },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -109,13 +116,37 @@ pub fn load_profile<T: CustomQuery>(
 pub fn load_profiles<T: CustomQuery>(
     querier: &QuerierWrapper<T>,
     profile_contract: String,
+    start_after: Option<Addr>,
     limit: u32,
-    start_at: Option<u64>,
+    order: Option<ProfileOrder>,
+) -> StdResult<Vec<Profile>> {
+    querier.query_wasm_smart(
+        profile_contract,
+        &QueryMsg::Profiles {
+            start_after,
+            limit,
+            order,
+        },
+    )
+}
+
+pub fn load_profiles_by_addrs<T: CustomQuery>(
+    querier: &QuerierWrapper<T>,
+    profile_contract: String,
+    addrs: Vec<Addr>,
 ) -> StdResult<Vec<Profile>> {
-    querier.query_wasm_smart(profile_contract, &QueryMsg::Profiles { limit, start_at })
+    querier.query_wasm_smart(profile_contract, &QueryMsg::ProfilesByAddrs { addrs })
 }
 
 // Data
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileOrder {
+    Address,             // ascending
+    ReleasedTradesCount, // descending, for leaderboards
+    CreatedAt,           // descending, newest first
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Profile {
     pub addr: Addr,
@@ -127,6 +158,10 @@ pub struct Profile {
     pub contact: Option<String>,
     pub encryption_key: Option<String>,
     pub active_offers_count: u8,
+    #[serde(default)]
+    pub last_seen: u64,
+    #[serde(default)]
+    pub missed_dispute_deadlines: u64, // disputes taken away from this arbitrator
 }
 
 impl Profile {
@@ -141,8 +176,15 @@ impl Profile {
             encryption_key: None,
             active_offers_count: 0,
             active_trades_count: 0,
+            last_seen: 0,
+            missed_dispute_deadlines: 0,
         }
     }
+
+    // A window of 0 disables the presence check
+    pub fn is_present(&self, presence_window: u64, block_time: u64) -> bool {
+        presence_window.eq(&0) || self.last_seen + presence_window >= block_time
+    }
 }
 
 // Model
@@ -178,34 +220,107 @@ impl ProfileModel<'_> {
             .unwrap_or(Profile::new(profile_addr, 0))
     }
 
+    // Profiles that don't exist yet are returned with default values, like `query_profile`
+    pub fn query_profiles_by_addrs(storage: &dyn Storage, addrs: Vec<Addr>) -> Vec<Profile> {
+        addrs
+            .into_iter()
+            .map(|addr| ProfileModel::query_profile(storage, addr))
+            .collect()
+    }
+
     pub fn save<'a>(self) -> Profile {
         ProfileModel::store(self.storage, &self.profile).profile
     }
 
     pub fn query_profiles(
         deps: Deps,
-        _env: Env,
+        start_after: Option<Addr>,
         limit: u32,
-        _start_at: Option<u64>,
+        order: Option<ProfileOrder>,
     ) -> StdResult<Vec<Profile>> {
-        let result = profiles()
-            .idx
-            .owner
-            .range(deps.storage, None, None, Order::Descending)
-            .take(limit as usize)
-            .flat_map(|item| item.map(|(_, profile)| profile))
-            .collect();
+        let limit = validate_min_max_items_per_page(limit) as usize;
+        let storage = deps.storage;
+
+        // The ordered indexes are keyed by (value, addr), so the cursor needs the value
+        // of the last profile returned.
+        let cursor = start_after.map(|addr| ProfileModel::query_profile(storage, addr));
+
+        let result = match order.unwrap_or(ProfileOrder::Address) {
+            ProfileOrder::Address => profiles()
+                .range(
+                    storage,
+                    cursor.map(|p| Bound::exclusive(p.addr.to_string())),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|item| item.map(|(_, profile)| profile))
+                .collect::<StdResult<Vec<Profile>>>()?,
+            ProfileOrder::ReleasedTradesCount => profiles()
+                .idx
+                .released_trades_count
+                .range(
+                    storage,
+                    None,
+                    cursor.map(|p| Bound::exclusive((p.released_trades_count, p.addr.to_string()))),
+                    Order::Descending,
+                )
+                .take(limit)
+                .map(|item| item.map(|(_, profile)| profile))
+                .collect::<StdResult<Vec<Profile>>>()?,
+            ProfileOrder::CreatedAt => profiles()
+                .idx
+                .created_at
+                .range(
+                    storage,
+                    None,
+                    cursor.map(|p| Bound::exclusive((p.created_at, p.addr.to_string()))),
+                    Order::Descending,
+                )
+                .take(limit)
+                .map(|item| item.map(|(_, profile)| profile))
+                .collect::<StdResult<Vec<Profile>>>()?,
+        };
         Ok(result)
     }
+
+    /// Saves every profile again so the indexes added after they were created are populated.
+    pub fn reindex(storage: &mut dyn Storage) -> StdResult<()> {
+        let all_profiles: Vec<Profile> = profiles()
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, profile)| profile))
+            .collect::<StdResult<Vec<Profile>>>()?;
+        for profile in all_profiles {
+            profiles().save(storage, profile.addr.to_string(), &profile)?;
+        }
+        Ok(())
+    }
+
+    // Profiles stored before presence tracking are considered seen at migration time,
+    // so their offers stay listed until the presence window elapses
+    pub fn backfill_last_seen(storage: &mut dyn Storage, block_time: u64) -> StdResult<()> {
+        let all_profiles: Vec<Profile> = profiles()
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, profile)| profile))
+            .collect::<StdResult<Vec<Profile>>>()?;
+        for mut profile in all_profiles.into_iter().filter(|p| p.last_seen.eq(&0)) {
+            profile.last_seen = block_time;
+            profiles().save(storage, profile.addr.to_string(), &profile)?;
+        }
+        Ok(())
+    }
 }
 
 pub struct ProfileIndexes<'a> {
     pub owner: MultiIndex<'a, Addr, Profile, String>,
+    pub released_trades_count: MultiIndex<'a, u64, Profile, String>,
+    pub created_at: MultiIndex<'a, u64, Profile, String>,
 }
 
 impl<'a> IndexList<Profile> for ProfileIndexes<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Profile>> + '_> {
-        let v: Vec<&dyn Index<Profile>> = vec![&self.owner];
+        let v: Vec<&dyn Index<Profile>> =
+            vec![&self.owner, &self.released_trades_count, &self.created_at];
         Box::new(v.into_iter())
     }
 }
@@ -217,6 +332,16 @@ pub fn profiles() -> IndexedMap<String, Profile, ProfileIndexes<'static>> {
             "profiles",
             "profiles__owner",
         ),
+        released_trades_count: MultiIndex::new(
+            |_, d: &Profile| d.released_trades_count,
+            "profiles",
+            "profiles__released_trades_count",
+        ),
+        created_at: MultiIndex::new(
+            |_, d: &Profile| d.created_at,
+            "profiles",
+            "profiles__created_at",
+        ),
     };
     IndexedMap::new("profiles", indexes)
 }
@@ -0,0 +1,142 @@
+use cosmwasm_std::{Api, Binary, HashFunction, StdError, StdResult, BLS12_381_G2_GENERATOR};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+// Domain separation tag of drand's unchained G1 scheme (quicknet)
+const DRAND_G1_DST: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// A drand style randomness beacon publishing unchained BLS signatures on G1.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RandomnessBeacon {
+    pub public_key: Binary, // compressed G2 point
+    pub genesis_time: u64,  // in seconds, when round 1 was published
+    pub period: u64,        // in seconds between rounds
+}
+
+impl RandomnessBeacon {
+    /// The first round published after the given time, its signature can't be known before.
+    pub fn round_after(&self, time: u64) -> u64 {
+        if time < self.genesis_time {
+            1
+        } else {
+            (time - self.genesis_time) / self.period + 2
+        }
+    }
+
+    /// Verifies the signature of a round: e(signature, G2) == e(H(sha256(round)), public_key).
+    pub fn verify(&self, api: &dyn Api, round: u64, signature: &[u8]) -> StdResult<bool> {
+        let message = Sha256::digest(round.to_be_bytes());
+        let message_point = api
+            .bls12_381_hash_to_g1(HashFunction::Sha256, &message, DRAND_G1_DST)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        api.bls12_381_pairing_equality(
+            signature,
+            &BLS12_381_G2_GENERATOR,
+            &message_point,
+            &self.public_key,
+        )
+        .map_err(|e| StdError::generic_err(e.to_string()))
+    }
+}
+
+/// Hashes any source of entropy into a seed.
+pub fn randomness_seed(entropy: &[u8]) -> [u8; 32] {
+    Sha256::digest(entropy).into()
+}
+
+/// Derives the nth random number of a seed.
+pub fn random_value(seed: &[u8; 32], n: u64) -> u64 {
+    let hash = Sha256::new()
+        .chain_update(seed)
+        .chain_update(n.to_be_bytes())
+        .finalize();
+    u64::from_be_bytes(hash[..8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockApi;
+    use cosmwasm_std::HexBinary;
+
+    // drand quicknet, chain 52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971
+    const QUICKNET_PUBLIC_KEY: &str = concat!(
+        "83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c",
+        "8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb",
+        "5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a",
+    );
+    const QUICKNET_ROUND_2: &str = concat!(
+        "b6b6a585449b66eb12e875b64fcbab3799861a00e4dbf092d99e969a5eac57dd",
+        "3f798acf61e705fe4f093db926626807",
+    );
+    const QUICKNET_ROUND_1000: &str = concat!(
+        "b44679b9a59af2ec876b1a6b1ad52ea9b1615fc3982b19576350f93447cb1125",
+        "e342b73a8dd2bacbe47e4b6b63ed5e39",
+    );
+
+    fn quicknet() -> RandomnessBeacon {
+        RandomnessBeacon {
+            public_key: HexBinary::from_hex(QUICKNET_PUBLIC_KEY).unwrap().into(),
+            genesis_time: 1692803367,
+            period: 3,
+        }
+    }
+
+    fn signature(hex: &str) -> Vec<u8> {
+        HexBinary::from_hex(hex).unwrap().to_vec()
+    }
+
+    #[test]
+    fn verifies_quicknet_rounds() {
+        let api = MockApi::default();
+        let beacon = quicknet();
+        assert!(beacon.verify(&api, 2, &signature(QUICKNET_ROUND_2)).unwrap());
+        assert!(beacon.verify(&api, 1000, &signature(QUICKNET_ROUND_1000)).unwrap());
+    }
+
+    #[test]
+    fn rejects_signature_of_another_round() {
+        let api = MockApi::default();
+        let beacon = quicknet();
+        assert!(!beacon.verify(&api, 1001, &signature(QUICKNET_ROUND_1000)).unwrap());
+        assert!(!beacon.verify(&api, 1000, &signature(QUICKNET_ROUND_2)).unwrap());
+    }
+
+    #[test]
+    fn rejects_signature_from_another_key() {
+        let api = MockApi::default();
+        let mut beacon = quicknet();
+        // The generator of G2 is a valid public key that didn't sign the round
+        beacon.public_key = BLS12_381_G2_GENERATOR.to_vec().into();
+        assert!(!beacon.verify(&api, 1000, &signature(QUICKNET_ROUND_1000)).unwrap());
+    }
+
+    #[test]
+    fn rejects_malformed_signature() {
+        let api = MockApi::default();
+        let mut malformed = signature(QUICKNET_ROUND_1000);
+        malformed.truncate(47);
+        assert!(quicknet().verify(&api, 1000, &malformed).is_err());
+    }
+
+    #[test]
+    fn rounds_are_published_after_the_given_time() {
+        let beacon = quicknet();
+        assert_eq!(beacon.round_after(0), 1);
+        assert_eq!(beacon.round_after(beacon.genesis_time), 2);
+        assert_eq!(beacon.round_after(beacon.genesis_time + 2), 2);
+        assert_eq!(beacon.round_after(beacon.genesis_time + 3), 3);
+        // Round 999 is published at genesis + 998 periods, the next one is round 1000
+        assert_eq!(beacon.round_after(beacon.genesis_time + 998 * 3), 1000);
+    }
+
+    #[test]
+    fn seeds_from_quicknet_round() {
+        let seed = randomness_seed(&signature(QUICKNET_ROUND_1000));
+        assert_eq!(
+            HexBinary::from(seed.as_slice()).to_hex(),
+            "fe290beca10872ef2fb164d2aa4442de4566183ec51c56ff3cd603d930e54fdd"
+        );
+    }
+}
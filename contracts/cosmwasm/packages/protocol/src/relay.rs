@@ -0,0 +1,193 @@
+use crate::errors::ContractError;
+use cosmwasm_std::{to_json_vec, Addr, Api, CanonicalAddr, StdError, StdResult};
+use ripemd::Ripemd160;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The trade actions a relayer can submit on behalf of a trader that has no gas tokens.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RelayedAction {
+    AcceptRequest {
+        trade_id: u64,
+        maker_contact: String,
+    },
+    FiatDeposited {
+        trade_id: u64,
+    },
+    ReleaseEscrow {
+        trade_id: u64,
+    },
+    CancelRequest {
+        trade_id: u64,
+    },
+}
+
+/// The payload signed by the trader, bound to the chain and the trade contract so it can't be
+/// replayed elsewhere, and to the signer's nonce so it can't be replayed here.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RelayAuthorization {
+    pub chain_id: String,
+    pub trade_contract: Addr,
+    pub action: RelayedAction,
+    pub nonce: u64,
+    pub deadline: u64,
+}
+
+impl RelayAuthorization {
+    /// The sha256 of the JSON encoded authorization, this is what the trader signs.
+    pub fn digest(&self) -> StdResult<[u8; 32]> {
+        Ok(Sha256::digest(to_json_vec(self)?).into())
+    }
+
+    pub fn verify(&self, api: &dyn Api, signature: &[u8], public_key: &[u8]) -> StdResult<bool> {
+        api.secp256k1_verify(&self.digest()?, signature, public_key)
+            .map_err(|e| StdError::generic_err(e.to_string()))
+    }
+
+    /// Checks that the authorization is still valid, signed by the given public key and uses
+    /// the next nonce of its signer.
+    pub fn authorize(
+        &self,
+        api: &dyn Api,
+        block_time: u64,
+        signature: &[u8],
+        public_key: &[u8],
+        expected_nonce: u64,
+    ) -> Result<(), ContractError> {
+        if block_time > self.deadline {
+            return Err(ContractError::RelayDeadlinePassed {
+                deadline: self.deadline,
+            });
+        }
+        if !self.verify(api, signature, public_key)? {
+            return Err(ContractError::InvalidRelaySignature {});
+        }
+        if self.nonce.ne(&expected_nonce) {
+            return Err(ContractError::InvalidRelayNonce {
+                expected: expected_nonce,
+                received: self.nonce,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The account address of a compressed secp256k1 public key: ripemd160(sha256(public_key)).
+pub fn secp256k1_pubkey_to_addr(api: &dyn Api, public_key: &[u8]) -> StdResult<Addr> {
+    if public_key.len() != 33 {
+        return Err(StdError::generic_err("Expected a compressed secp256k1 public key."));
+    }
+    let hash = Ripemd160::digest(Sha256::digest(public_key));
+    api.addr_humanize(&CanonicalAddr::from(hash.as_slice()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockApi;
+    use cosmwasm_std::HexBinary;
+
+    // secp256k1 key with the secret sha256("trader")
+    const TRADER_PUBLIC_KEY: &str =
+        "02a476bdde2ad4e5a6bc852c5b97c3c20062a8063fa495b19ff6e9c9b1c3c239d6";
+    // secp256k1 key with the secret sha256("other")
+    const OTHER_PUBLIC_KEY: &str =
+        "0353fd1af2ef9cd4fe2b96ed6bd67a97d8830984bd0769be74209387bf7c9cf5d2";
+    // Signature of the trader over authorization()
+    const RELAY_SIGNATURE: &str = concat!(
+        "5db17d10002bd5f5a0e917c897e3c608760d8a7f14f1af8e88a17215f2406aaf",
+        "2ff29b283fb3113d28bcc17d869daf8c6671296ade9de4637255ab51f7b9de82",
+    );
+
+    fn authorization() -> RelayAuthorization {
+        RelayAuthorization {
+            chain_id: "localmoney-1".to_string(),
+            trade_contract: Addr::unchecked("trade"),
+            action: RelayedAction::ReleaseEscrow { trade_id: 1 },
+            nonce: 0,
+            deadline: 1000,
+        }
+    }
+
+    fn hex(value: &str) -> Vec<u8> {
+        HexBinary::from_hex(value).unwrap().to_vec()
+    }
+
+    fn authorize(
+        authorization: &RelayAuthorization,
+        block_time: u64,
+        public_key: &str,
+        expected_nonce: u64,
+    ) -> Result<(), ContractError> {
+        let api = MockApi::default();
+        let signature = hex(RELAY_SIGNATURE);
+        authorization.authorize(&api, block_time, &signature, &hex(public_key), expected_nonce)
+    }
+
+    #[test]
+    fn digests_the_json_encoded_authorization() {
+        // sha256 of {"chain_id":"localmoney-1","trade_contract":"trade",
+        // "action":{"release_escrow":{"trade_id":1}},"nonce":0,"deadline":1000}
+        assert_eq!(
+            HexBinary::from(authorization().digest().unwrap().as_slice()).to_hex(),
+            "26cc0f252e2cd58e000233407a4dc66475ad8090a066a4ce883a6515b7ab380f"
+        );
+    }
+
+    #[test]
+    fn authorizes_valid_relay() {
+        assert!(authorize(&authorization(), 1000, TRADER_PUBLIC_KEY, 0).is_ok());
+    }
+
+    #[test]
+    fn rejects_replayed_nonce() {
+        let err = authorize(&authorization(), 1000, TRADER_PUBLIC_KEY, 1).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidRelayNonce {
+                expected: 1,
+                received: 0,
+            }
+        ));
+    }
+
+    #[test]
+    fn rejects_another_chain_or_contract() {
+        let mut other_chain = authorization();
+        other_chain.chain_id = "localmoney-2".to_string();
+        let err = authorize(&other_chain, 1000, TRADER_PUBLIC_KEY, 0).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidRelaySignature {}));
+
+        let mut other_contract = authorization();
+        other_contract.trade_contract = Addr::unchecked("other_trade");
+        let err = authorize(&other_contract, 1000, TRADER_PUBLIC_KEY, 0).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidRelaySignature {}));
+    }
+
+    #[test]
+    fn rejects_expired_deadline() {
+        let err = authorize(&authorization(), 1001, TRADER_PUBLIC_KEY, 0).unwrap_err();
+        assert!(matches!(err, ContractError::RelayDeadlinePassed { deadline: 1000 }));
+    }
+
+    #[test]
+    fn rejects_public_key_of_another_signer() {
+        let err = authorize(&authorization(), 1000, OTHER_PUBLIC_KEY, 0).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidRelaySignature {}));
+    }
+
+    #[test]
+    fn derives_signer_address_from_public_key() {
+        let api = MockApi::default();
+        let signer = secp256k1_pubkey_to_addr(&api, &hex(TRADER_PUBLIC_KEY)).unwrap();
+        // ripemd160(sha256(public_key))
+        assert_eq!(
+            api.addr_canonicalize(signer.as_str()).unwrap().to_string(),
+            "43431C4C89F1840E18C35FC84F8F11AA47F1997A"
+        );
+        let uncompressed = [4u8; 65];
+        assert!(secp256k1_pubkey_to_addr(&api, &uncompressed).is_err());
+    }
+}
@@ -2,8 +2,8 @@ use std::fmt::{self, Display};
 use std::ops::{Add};
 
 use cosmwasm_std::{
-    Addr, BlockInfo, Coin, CustomQuery, Deps, Env, MessageInfo, Order, StdResult, Storage,
-    Uint128, Uint256,
+    Addr, Binary, BlockInfo, Coin, CustomQuery, Decimal, Deps, Env, MessageInfo, Order,
+    StdResult, Storage, Uint128, Uint256,
 };
 use cw20::Denom;
 use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
@@ -11,15 +11,63 @@ use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::attestation::Attestor;
+use crate::constants::MAX_SCANNED_TRADES;
 use crate::currencies::FiatCurrency;
-use crate::guards::assert_range_0_to_99;
-use crate::offer::Arbitrator;
+use crate::guards::{assert_range_0_to_99, validate_min_max_items_per_page};
+use crate::hub::HubConfig;
+use crate::offer::{Arbitrator, OfferTimers, TradeInfo};
 use crate::profile::Profile;
+use crate::relay::RelayedAction;
 
 pub const DENOM_CONVERSION_ROUTE: Map<&str, Vec<ConversionRoute>> =
     Map::new("denom_conversion_route");
 pub const DENOM_CONVERSION_STEP: Item<ConversionStep> = Item::new("denom_conversion_step");
 
+// Trade counters maintained on every state transition, keyed by state and by (fiat, state)
+pub const TRADES_COUNT_BY_STATE: Map<&str, u64> = Map::new("trades_count_by_state");
+pub const TRADES_COUNT_BY_FIAT_STATE: Map<(&str, &str), FiatTradesCount> =
+    Map::new("trades_count_by_fiat_state");
+
+// Both the buyer and the seller of a trade point to the trade id
+pub const TRADE_PARTICIPANTS: Map<(&Addr, u64), u64> = Map::new("trade_participants");
+
+// Every member of a dispute panel points to the trade id
+pub const DISPUTE_PANELS: Map<(&Addr, u64), u64> = Map::new("dispute_panels");
+
+// Disputes each arbitrator is currently deciding, alone or on a panel
+pub const ARBITRATOR_OPEN_DISPUTES: Map<&Addr, u32> = Map::new("arbitrator_open_disputes");
+
+// Track record of every arbitrator that was assigned a dispute
+pub const ARBITRATOR_STATS: Map<&Addr, ArbitratorStats> = Map::new("arbitrator_stats");
+
+// Incremented every time an arbitrator rotates its encryption key, starting at 0
+pub const ARBITRATOR_KEY_VERSIONS: Map<&Addr, u32> = Map::new("arbitrator_key_versions");
+
+// Arbitrators that didn't set their availability take any number of disputes
+pub const ARBITRATOR_AVAILABILITY: Map<&Addr, ArbitratorAvailability> =
+    Map::new("arbitrator_availability");
+
+// Applications waiting for approval, and the bonds of approved arbitrators
+pub const ARBITRATOR_APPLICATIONS: Map<&Addr, ArbitratorApplication> =
+    Map::new("arbitrator_applications");
+pub const ARBITRATOR_BONDS: Map<&Addr, ArbitratorBond> = Map::new("arbitrator_bonds");
+
+// Arbitrators a trader doesn't want deciding their disputes
+pub const ARBITRATOR_CONFLICTS: Map<&Addr, Vec<Addr>> = Map::new("arbitrator_conflicts");
+
+// Verifiers whose signed payment attestations release the escrow of opted-in offers
+pub const ATTESTORS: Map<&Addr, Attestor> = Map::new("attestors");
+
+// Next nonce expected from each signer of relayed actions
+pub const RELAY_NONCES: Map<&Addr, u64> = Map::new("relay_nonces");
+
+// Append-only evidence log of each dispute, keyed by (trade_id, entry id)
+pub const DISPUTE_EVIDENCE: Map<(u64, u32), DisputeEvidence> = Map::new("dispute_evidence");
+pub const DISPUTE_EVIDENCE_COUNT: Map<u64, u32> = Map::new("dispute_evidence_count");
+pub const DISPUTE_EVIDENCE_AUTHOR_COUNT: Map<(u64, &Addr), u32> =
+    Map::new("dispute_evidence_author_count");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {}
 
@@ -43,8 +91,8 @@ pub enum ExecuteMsg {
     },
     DisputeEscrow {
         trade_id: u64,
-        buyer_contact: String,
-        seller_contact: String,
+        buyer_contact: Option<String>,
+        seller_contact: Option<String>,
     },
     FiatDeposited {
         trade_id: u64,
@@ -56,6 +104,7 @@ pub enum ExecuteMsg {
         arbitrator: Addr,
         fiat: FiatCurrency,
         encryption_key: String,
+        weight: Option<u32>,
     },
     DeleteArbitrator {
         arbitrator: Addr,
@@ -64,12 +113,110 @@ pub enum ExecuteMsg {
     SettleDispute {
         trade_id: u64,
         winner: Addr,
+        decision_hash: Option<String>,
+    },
+    SettleDisputeSplit {
+        trade_id: u64,
+        buyer_share: Decimal,
+        decision_hash: Option<String>,
     },
     RegisterHub {},
     RegisterConversionRouteForDenom {
         denom: Denom,
         route: Vec<ConversionRoute>,
     },
+    ExpireTrades {
+        limit: u32,
+    },
+    ProposeCancel {
+        trade_id: u64,
+    },
+    ConfirmCancel {
+        trade_id: u64,
+    },
+    PartialRelease {
+        trade_id: u64,
+        amount: Uint128,
+    },
+    ReassignArbitrator {
+        trade_id: u64,
+    },
+    VoteDispute {
+        trade_id: u64,
+        winner: Addr,
+        decision_hash: Option<String>,
+    },
+    DrawArbitrator {
+        trade_id: u64,
+        signature: Binary,
+    },
+    UpdateArbitratorContact {
+        trade_id: u64,
+        contact: String,
+    },
+    SetArbitratorConflicts {
+        arbitrators: Vec<Addr>,
+    },
+    ApplyArbitrator {
+        fiats: Vec<FiatCurrency>,
+        languages: Vec<String>,
+        encryption_key: String,
+    },
+    ApproveArbitrator {
+        applicant: Addr,
+    },
+    RejectArbitrator {
+        applicant: Addr,
+    },
+    UnbondArbitrator {},
+    ClaimArbitratorBond {},
+    SlashArbitrator {
+        arbitrator: Addr,
+        amount: Uint128,
+        recipient: Addr,
+    },
+    SetArbitratorAvailability {
+        available: bool,
+        max_open_disputes: Option<u32>,
+    },
+    RotateArbitratorKey {
+        encryption_key: String,
+    },
+    AppealDispute {
+        trade_id: u64,
+    },
+    FinalizeSettlement {
+        trade_id: u64,
+    },
+    SubmitEvidence {
+        trade_id: u64,
+        evidence_hash: String,
+        uri: Option<String>,
+    },
+    RequestEvidence {
+        trade_id: u64,
+        party: Addr,
+        description: String,
+    },
+    NewAttestor {
+        attestor: Addr,
+        public_key: Binary,
+        description: String,
+    },
+    DeleteAttestor {
+        attestor: Addr,
+    },
+    SubmitPaymentAttestation {
+        trade_id: u64,
+        signature: Binary,
+    },
+    RelayAction {
+        action: RelayedAction,
+        signer_pubkey: Binary,
+        signature: Binary,
+        nonce: u64,
+        deadline: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -87,10 +234,42 @@ pub enum QueryMsg {
     Arbitrator {
         arbitrator: Addr,
     },
-    Arbitrators {},
+    Arbitrators {
+        fiat: Option<FiatCurrency>,
+        start_after: Option<(Addr, FiatCurrency)>, // the last arbitrator and fiat of a page
+        limit: u32,
+    },
+    ArbitratorStats {
+        arbitrator: Addr,
+    },
+    ArbitratorApplications {
+        start_after: Option<Addr>,
+        limit: u32,
+    },
+    ArbitratorBond {
+        arbitrator: Addr,
+    },
+    ArbitratorAvailability {
+        arbitrator: Addr,
+    },
     ArbitratorsFiat {
         fiat: FiatCurrency,
     },
+    ArbitratorConflicts {
+        user: Addr,
+    },
+    DisputeEvidence {
+        trade_id: u64,
+        start_after: Option<u32>,
+        limit: u32,
+    },
+    Attestors {
+        start_after: Option<Addr>,
+        limit: u32,
+    },
+    RelayNonce {
+        signer: Addr,
+    },
     TradesCountByStates {
         states: Vec<TradeState>,
     },
@@ -101,6 +280,17 @@ pub enum QueryMsg {
     AllFiatsTradesCount {
         states: Vec<TradeState>,
     },
+    TradesFiltered {
+        user: Addr,
+        states: Option<Vec<TradeState>>,
+        fiat: Option<FiatCurrency>,
+        denom: Option<Denom>,
+        offer_id: Option<u64>,
+        created_after: Option<u64>,
+        created_before: Option<u64>,
+        start_after: Option<u64>,
+        limit: u32,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -199,9 +389,18 @@ pub enum TradeState {
     EscrowRefunded,
     FiatDeposited,
     EscrowReleased,
+    EscrowPartiallyReleased,
     EscrowDisputed,
     SettledForMaker,
     SettledForTaker,
+    SettledSplit,
+    DisputeDecided, // the funds stay in escrow until the appeal window closes
+    // Only recorded in the state history, the trade keeps its state until the cancel is confirmed
+    CancelProposed,
+    // Only recorded in the state history, the dispute moved to a new arbitrator
+    ArbitratorReassigned,
+    // Only recorded in the state history, the decision goes back to EscrowDisputed for review
+    DisputeAppealed,
 }
 
 impl fmt::Display for TradeState {
@@ -218,7 +417,7 @@ pub struct Trade {
     pub buyer_contact: Option<String>,
     pub seller: Addr,
     pub seller_contact: Option<String>,
-    pub arbitrator: Addr,
+    pub arbitrator: Option<Addr>, // drawn when the dispute is opened
     pub arbitrator_buyer_contact: Option<String>,
     pub arbitrator_seller_contact: Option<String>,
     pub offer_contract: Addr,
@@ -232,6 +431,210 @@ pub struct Trade {
     pub denom_fiat_price: Uint256,
     pub state_history: Vec<TradeStateItem>,
     state: TradeState,
+    #[serde(default)]
+    pub timers: TradeTimers,
+    #[serde(default)]
+    pub escrow_fees: Uint128, // fees sent on top of the amount when the maker funded the escrow
+    pub cancel_proposed_by: Option<Addr>,
+    pub released_amount: Option<Uint128>, // set when only part of the escrow was released
+    pub dispute_deadline: Option<u64>,    // after it the dispute can move to another arbitrator
+    #[serde(default)]
+    pub usd_amount: Uint128, // trade amount in USD when it was created
+    #[serde(default)]
+    pub panel: Vec<Addr>, // arbitrators voting on a high value dispute, starting with `arbitrator`
+    #[serde(default)]
+    pub votes: Vec<DisputeVote>,
+    pub arbitrator_round: Option<u64>, // beacon round that draws the arbitrators
+    #[serde(default)]
+    pub pending_replacements: Vec<Addr>, // arbitrators replaced once the round is drawn
+    // Version of the arbitrator key each arbitrator contact was encrypted with
+    pub arbitrator_buyer_contact_key_version: Option<u32>,
+    pub arbitrator_seller_contact_key_version: Option<u32>,
+    pub decision: Option<DisputeDecision>, // set while the decision can be appealed
+    pub appeal: Option<DisputeAppeal>,
+    pub decision_hash: Option<String>, // hash of the arbitrator's reasoning
+    #[serde(default)]
+    pub payment_attestation: bool, // copied from the offer when the trade is created
+    #[serde(default)]
+    pub dispute_candidates: Vec<DisputeCandidate>, // arbitrators the dispute is drawn from
+}
+
+/// An arbitrator a dispute can be drawn from, snapshotted when the dispute is opened.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DisputeCandidate {
+    pub arbitrator: Addr,
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DisputeVote {
+    pub arbitrator: Addr,
+    pub winner: Addr,
+    #[serde(default)]
+    pub decision_hash: Option<String>, // hash of the panel member's reasoning
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeOutcome {
+    Winner { winner: Addr },
+    Split { buyer_share: Decimal },
+}
+
+/// A decision waiting for the appeal window to close before its funds are released.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DisputeDecision {
+    pub outcome: DisputeOutcome,
+    pub arbitrators: Vec<Addr>, // the ones that decided it, they share the arbitration fee
+    pub decided_at: u64,
+    pub appeal_deadline: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DisputeAppeal {
+    pub appellant: Addr,
+    pub bond: Uint128, // in the trade denom
+    pub appealed_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeEvidenceKind {
+    Submitted,
+    Requested,
+}
+
+/// An entry of the evidence log, the evidence itself stays off-chain and is committed by its hash.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DisputeEvidence {
+    pub id: u32,
+    pub trade_id: u64,
+    pub kind: DisputeEvidenceKind,
+    pub author: Addr,
+    pub evidence_hash: Option<String>, // hex encoded sha256 of the submitted evidence
+    pub uri: Option<String>,
+    pub party: Option<Addr>, // the party evidence is requested from
+    pub description: Option<String>,
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ArbitratorStats {
+    pub assigned_disputes: u64,
+    pub resolved_disputes: u64,
+    pub settled_for_maker: u64,
+    pub settled_for_taker: u64,
+    pub settled_split: u64,
+    pub reassignments: u64, // disputes taken away after missing the deadline
+    pub total_resolution_time: u64,
+    pub average_resolution_time: u64, // in seconds, since the dispute was opened
+    pub fees_earned: Vec<Coin>,
+    #[serde(default)]
+    pub last_dispute_closed_at: u64, // settled or reassigned
+    #[serde(default)]
+    pub appealed_decisions: u64,
+    #[serde(default)]
+    pub overturned_decisions: u64, // appeals that reversed the decision
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ArbitratorAvailability {
+    pub available: bool,
+    pub max_open_disputes: Option<u32>, // no limit if unset
+}
+
+impl Default for ArbitratorAvailability {
+    fn default() -> Self {
+        ArbitratorAvailability {
+            available: true,
+            max_open_disputes: None,
+        }
+    }
+}
+
+impl ArbitratorAvailability {
+    pub fn accepts_disputes(&self, open_disputes: u32) -> bool {
+        self.available && self.max_open_disputes.is_none_or(|max| open_disputes < max)
+    }
+}
+
+/// A candidate waiting for the hub admin to approve it as an arbitrator.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ArbitratorApplication {
+    pub applicant: Addr,
+    pub fiats: Vec<FiatCurrency>,
+    pub languages: Vec<String>,
+    pub encryption_key: String,
+    pub bond: Uint128, // in the hub local_denom
+    pub applied_at: u64,
+}
+
+/// Stake an approved arbitrator can lose when an appeal overturns its decision.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ArbitratorBond {
+    pub arbitrator: Addr,
+    pub amount: Uint128, // in the hub local_denom
+    pub slashed_amount: Uint128,
+    pub unbonding_since: Option<u64>,
+}
+
+impl ArbitratorStats {
+    pub fn register_resolution(
+        &mut self,
+        trade_state: &TradeState,
+        resolution_time: u64,
+        fee: Coin,
+    ) {
+        match trade_state {
+            TradeState::SettledForMaker => self.settled_for_maker += 1,
+            TradeState::SettledForTaker => self.settled_for_taker += 1,
+            TradeState::SettledSplit => self.settled_split += 1,
+            _ => {}
+        }
+        self.resolved_disputes += 1;
+        self.total_resolution_time += resolution_time;
+        self.average_resolution_time = self.total_resolution_time / self.resolved_disputes;
+
+        if fee.amount.is_zero() {
+            return;
+        }
+        match self.fees_earned.iter_mut().find(|c| c.denom.eq(&fee.denom)) {
+            Some(earned) => earned.amount += fee.amount,
+            None => self.fees_earned.push(fee),
+        }
+    }
+}
+
+/// The phase timers of a trade, in seconds, fixed when the trade is created.
+/// Missing the accept or funding deadline expires the request, missing the payment deadline
+/// allows the seller to be refunded and missing the release deadline allows a dispute.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct TradeTimers {
+    pub accept: u64,  // maker accepts the request of a buy offer
+    pub funding: u64, // seller funds the escrow
+    pub payment: u64, // buyer deposits the fiat
+    pub release: u64, // seller releases the escrow
+}
+
+impl TradeTimers {
+    pub fn new(hub_config: &HubConfig, offer_timers: &Option<OfferTimers>) -> TradeTimers {
+        let or_default = |timer: u64, default: u64| if timer.eq(&0) { default } else { timer };
+        let hub_timers = TradeTimers {
+            accept: or_default(hub_config.trade_accept_timer, hub_config.trade_expiration_timer),
+            funding: or_default(hub_config.trade_funding_timer, hub_config.trade_expiration_timer),
+            payment: or_default(hub_config.trade_payment_timer, hub_config.trade_expiration_timer),
+            release: or_default(hub_config.trade_release_timer, hub_config.trade_dispute_timer),
+        };
+        match offer_timers {
+            None => hub_timers,
+            Some(offer_timers) => TradeTimers {
+                accept: offer_timers.accept.unwrap_or(hub_timers.accept),
+                funding: offer_timers.funding.unwrap_or(hub_timers.funding),
+                payment: offer_timers.payment.unwrap_or(hub_timers.payment),
+                release: offer_timers.release.unwrap_or(hub_timers.release),
+            },
+        }
+    }
 }
 
 impl Trade {
@@ -242,7 +645,7 @@ impl Trade {
         seller: Addr,
         seller_contact: Option<String>,
         buyer_contact: Option<String>,
-        arbitrator: Addr,
+        arbitrator: Option<Addr>,
         offer_contract: Addr,
         offer_id: u64,
         created_at: u64,
@@ -274,6 +677,45 @@ impl Trade {
             denom_fiat_price,
             state_history,
             state: TradeState::RequestCreated,
+            timers: TradeTimers::default(),
+            escrow_fees: Uint128::zero(),
+            cancel_proposed_by: None,
+            released_amount: None,
+            dispute_deadline: None,
+            usd_amount: Uint128::zero(),
+            panel: vec![],
+            votes: vec![],
+            arbitrator_round: None,
+            pending_replacements: vec![],
+            arbitrator_buyer_contact_key_version: None,
+            arbitrator_seller_contact_key_version: None,
+            decision: None,
+            appeal: None,
+            decision_hash: None,
+            payment_attestation: false,
+            dispute_candidates: vec![],
+        }
+    }
+
+    // Trades created before the phase timers use the current hub timers
+    pub fn phase_timers(&self, hub_config: &HubConfig) -> TradeTimers {
+        if self.timers.eq(&TradeTimers::default()) {
+            TradeTimers::new(hub_config, &None)
+        } else {
+            self.timers.clone()
+        }
+    }
+
+    // The deadline of the current phase, the release phase ends when a dispute is enabled
+    pub fn phase_deadline(&self) -> Option<u64> {
+        if self.is_expirable() {
+            Some(self.expires_at)
+        } else if self.state.eq(&TradeState::FiatDeposited) {
+            self.enables_dispute_at
+        } else if self.state.eq(&TradeState::EscrowDisputed) {
+            self.dispute_deadline
+        } else {
+            None
         }
     }
 
@@ -285,6 +727,45 @@ impl Trade {
         self.expires_at.ne(&0) && block_time > self.expires_at
     }
 
+    // Trades waiting on the counterparty that will expire once expires_at is reached
+    pub fn is_expirable(&self) -> bool {
+        self.expires_at.ne(&0)
+            && [
+                TradeState::RequestCreated,
+                TradeState::RequestAccepted,
+                TradeState::EscrowFunded,
+            ]
+            .contains(&self.state)
+    }
+
+    // When the dispute was last opened, an appeal opens it again
+    pub fn disputed_at(&self) -> Option<u64> {
+        self.state_history
+            .iter()
+            .rev()
+            .find(|item| item.state.eq(&TradeState::EscrowDisputed))
+            .map(|item| item.timestamp)
+    }
+
+    // The arbitrators deciding the dispute, a panel already includes the arbitrator
+    pub fn dispute_arbitrators(&self) -> Vec<Addr> {
+        if self.panel.is_empty() {
+            self.arbitrator.clone().into_iter().collect()
+        } else {
+            self.panel.clone()
+        }
+    }
+
+    // The state as seen by clients, expired requests are shown as RequestExpired
+    pub fn current_state(&self, block_time: u64) -> TradeState {
+        // Swept or refunded trades keep their stored state
+        if self.is_expirable() && self.request_expired(block_time) {
+            TradeState::RequestExpired
+        } else {
+            self.get_state()
+        }
+    }
+
     pub fn set_state(&mut self, new_state: TradeState, env: &Env, info: &MessageInfo) {
         // if the escrow is canceled or fiat is already deposited, the trade can no longer expire
         if [TradeState::RequestCanceled,
@@ -295,6 +776,9 @@ impl Trade {
             self.expires_at = 0;
         }
 
+        // A cancel proposal only holds for the state it was made in
+        self.cancel_proposed_by = None;
+
         let block: BlockInfo = env.block.clone();
         self.state = new_state;
         let new_trade_state = TradeStateItem {
@@ -318,8 +802,11 @@ pub struct TradeResponse {
     pub seller_encryption_key: Option<String>,
     pub arbitrator: Option<Addr>,
     pub arbitrator_encryption_key: Option<String>,
+    pub arbitrator_key_version: Option<u32>, // contacts with another version must be re-sent
     pub arbitrator_seller_contact: Option<String>,
+    pub arbitrator_seller_contact_key_version: Option<u32>,
     pub arbitrator_buyer_contact: Option<String>,
+    pub arbitrator_buyer_contact_key_version: Option<u32>,
     pub offer_contract: Addr,
     pub offer_id: u64,
     pub created_at: u64,
@@ -331,6 +818,14 @@ pub struct TradeResponse {
     pub denom_fiat_price: Uint256,
     pub state_history: Vec<TradeStateItem>,
     pub state: TradeState,
+    pub phase_deadline: Option<u64>,
+    pub released_amount: Option<Uint128>,
+    pub panel: Vec<Addr>,
+    pub votes: Vec<DisputeVote>,
+    pub decision: Option<DisputeDecision>,
+    pub appeal: Option<DisputeAppeal>,
+    pub decision_hash: Option<String>,
+    pub payment_attestation: bool,
 }
 
 impl TradeResponse {
@@ -338,29 +833,32 @@ impl TradeResponse {
         trade: Trade,
         buyer_profile: Profile,
         seller_profile: Profile,
-        arbitrator_profile: Profile,
+        arbitrator_profile: Option<Profile>,
+        arbitrator_key_version: Option<u32>,
         block_time: u64,
     ) -> TradeResponse {
         let trade_states = [TradeState::EscrowDisputed,
             TradeState::SettledForMaker,
-            TradeState::SettledForTaker];
-        let state = if trade.request_expired(block_time) {
-            TradeState::RequestExpired
-        } else {
-            trade.get_state()
-        };
+            TradeState::SettledForTaker,
+            TradeState::SettledSplit,
+            TradeState::DisputeDecided];
+        let state = trade.current_state(block_time);
+        let phase_deadline = trade.phase_deadline();
 
         let arbitrator_address: Option<Addr> = if trade_states.contains(&state) {
-            Some(trade.arbitrator)
+            trade.arbitrator
         } else {
             None
         };
 
-        let arbitrator_encryption_key: Option<String> = if state.eq(&TradeState::FiatDeposited) {
-            arbitrator_profile.encryption_key.clone()
-        } else {
-            None
-        };
+        // The parties encrypt their contacts for the arbitrator once it is drawn
+        let (arbitrator_encryption_key, arbitrator_key_version) =
+            if state.eq(&TradeState::EscrowDisputed) {
+                let encryption_key = arbitrator_profile.and_then(|profile| profile.encryption_key);
+                (encryption_key, arbitrator_key_version)
+            } else {
+                (None, None)
+            };
 
         TradeResponse {
             id: trade.id,
@@ -373,8 +871,11 @@ impl TradeResponse {
             seller_encryption_key: seller_profile.encryption_key,
             arbitrator: arbitrator_address,
             arbitrator_encryption_key,
+            arbitrator_key_version,
             arbitrator_seller_contact: trade.arbitrator_seller_contact,
+            arbitrator_seller_contact_key_version: trade.arbitrator_seller_contact_key_version,
             arbitrator_buyer_contact: trade.arbitrator_buyer_contact,
+            arbitrator_buyer_contact_key_version: trade.arbitrator_buyer_contact_key_version,
             offer_contract: trade.offer_contract,
             offer_id: trade.offer_id,
             created_at: trade.created_at,
@@ -386,10 +887,54 @@ impl TradeResponse {
             denom_fiat_price: trade.denom_fiat_price,
             state_history: trade.state_history,
             state,
+            phase_deadline,
+            released_amount: trade.released_amount,
+            panel: trade.panel,
+            votes: trade.votes,
+            decision: trade.decision,
+            appeal: trade.appeal,
+            decision_hash: trade.decision_hash,
+            payment_attestation: trade.payment_attestation,
         }
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TradesFilter {
+    pub states: Option<Vec<TradeState>>,
+    pub fiat: Option<FiatCurrency>,
+    pub denom: Option<Denom>,
+    pub offer_id: Option<u64>,
+    pub created_after: Option<u64>,
+    pub created_before: Option<u64>,
+}
+
+impl TradesFilter {
+    pub fn matches(&self, trade: &Trade, block_time: u64) -> bool {
+        self.states
+            .as_ref()
+            .is_none_or(|states| states.contains(&trade.current_state(block_time)))
+            && self.fiat.as_ref().is_none_or(|fiat| trade.fiat.eq(fiat))
+            && self.denom.as_ref().is_none_or(|denom| trade.denom.eq(denom))
+            && self.offer_id.is_none_or(|offer_id| trade.offer_id.eq(&offer_id))
+            && self
+                .created_after
+                .is_none_or(|created_after| trade.created_at > created_after)
+            && self
+                .created_before
+                .is_none_or(|created_before| trade.created_at < created_before)
+    }
+
+}
+
+/// A page of the filtered trades query. The next page starts after `last_scanned`, there are
+/// no more trades once it is None.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TradesFilteredResponse {
+    pub trades: Vec<TradeInfo>,
+    pub last_scanned: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TradeStateItem {
     pub actor: Addr,
@@ -410,9 +955,124 @@ impl TradeModel<'_> {
     }
 
     pub fn store(storage: &mut dyn Storage, trade: &Trade) -> StdResult<()> {
+        let previous_trade = trades().may_load(storage, trade.id)?;
+        if let Some(previous_trade) = &previous_trade {
+            if previous_trade.panel.ne(&trade.panel) {
+                TradeModel::store_panel(storage, &previous_trade.panel, trade)?;
+            }
+        }
+        let previous_state = previous_trade.map(|t| t.state);
+        if previous_state.as_ref().ne(&Some(&trade.state)) {
+            match previous_state {
+                Some(previous_state) => {
+                    TradeModel::decrement_count(storage, &trade.fiat, &previous_state)?
+                }
+                None => TradeModel::store_participants(storage, trade)?,
+            }
+            TradeModel::increment_count(storage, &trade.fiat, &trade.state)?;
+        }
         trades().save(storage, trade.id, trade)
     }
 
+    fn store_participants(storage: &mut dyn Storage, trade: &Trade) -> StdResult<()> {
+        TRADE_PARTICIPANTS.save(storage, (&trade.buyer, trade.id), &trade.id)?;
+        TRADE_PARTICIPANTS.save(storage, (&trade.seller, trade.id), &trade.id)
+    }
+
+    fn store_panel(
+        storage: &mut dyn Storage,
+        previous_panel: &[Addr],
+        trade: &Trade,
+    ) -> StdResult<()> {
+        for arbitrator in previous_panel {
+            DISPUTE_PANELS.remove(storage, (arbitrator, trade.id));
+        }
+        for arbitrator in &trade.panel {
+            DISPUTE_PANELS.save(storage, (arbitrator, trade.id), &trade.id)?;
+        }
+        Ok(())
+    }
+
+    fn increment_count(
+        storage: &mut dyn Storage,
+        fiat: &FiatCurrency,
+        state: &TradeState,
+    ) -> StdResult<()> {
+        let state_key = state.to_string();
+        let fiat_key = fiat.to_string();
+        TRADES_COUNT_BY_STATE.update(storage, &state_key, |count| -> StdResult<u64> {
+            Ok(count.unwrap_or_default() + 1)
+        })?;
+        TRADES_COUNT_BY_FIAT_STATE.update(
+            storage,
+            (&fiat_key, &state_key),
+            |fiat_count| -> StdResult<FiatTradesCount> {
+                let mut fiat_count = fiat_count.unwrap_or(FiatTradesCount {
+                    fiat: fiat.clone(),
+                    count: 0,
+                });
+                fiat_count.count += 1;
+                Ok(fiat_count)
+            },
+        )?;
+        Ok(())
+    }
+
+    fn decrement_count(
+        storage: &mut dyn Storage,
+        fiat: &FiatCurrency,
+        state: &TradeState,
+    ) -> StdResult<()> {
+        let state_key = state.to_string();
+        let fiat_key = fiat.to_string();
+        TRADES_COUNT_BY_STATE.update(storage, &state_key, |count| -> StdResult<u64> {
+            Ok(count.unwrap_or_default().saturating_sub(1))
+        })?;
+        TRADES_COUNT_BY_FIAT_STATE.update(
+            storage,
+            (&fiat_key, &state_key),
+            |fiat_count| -> StdResult<FiatTradesCount> {
+                let mut fiat_count = fiat_count.unwrap_or(FiatTradesCount {
+                    fiat: fiat.clone(),
+                    count: 0,
+                });
+                fiat_count.count = fiat_count.count.saturating_sub(1);
+                Ok(fiat_count)
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Recounts the state counters from the stored trades, used by migrations.
+    pub fn backfill_counts(storage: &mut dyn Storage) -> StdResult<()> {
+        let all_trades: Vec<Trade> = trades()
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .map(|(_, trade)| trade)
+            .collect();
+        TRADES_COUNT_BY_STATE.clear(storage);
+        TRADES_COUNT_BY_FIAT_STATE.clear(storage);
+        for trade in all_trades {
+            TradeModel::increment_count(storage, &trade.fiat, &trade.state)?;
+        }
+        Ok(())
+    }
+
+    /// Indexes the buyer and seller of every stored trade, used by migrations.
+    pub fn backfill_participants(storage: &mut dyn Storage) -> StdResult<()> {
+        let all_trades: Vec<Trade> = trades()
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .map(|(_, trade)| trade)
+            .collect();
+        for trade in all_trades {
+            TradeModel::store_participants(storage, &trade)?;
+        }
+        Ok(())
+    }
+
     pub fn from_store(storage: &dyn Storage, id: u64) -> Trade {
         trades().may_load(storage, id).unwrap_or_default().unwrap()
     }
@@ -441,37 +1101,120 @@ impl TradeModel<'_> {
         limit: usize,
         last: Option<u64>,
     ) -> StdResult<Vec<Trade>> {
-        let range_from = last.map(Bound::exclusive);
-        let trader_addr = Addr::unchecked(trader);
+        TradeModel::trades_filtered(
+            storage,
+            Addr::unchecked(trader),
+            &TradesFilter::default(),
+            last,
+            limit,
+            0,
+        )
+        .map(|(trades, _)| trades)
+    }
 
-        // Get trades where user is buyer
-        let buyer_trades: Vec<Trade> = trades()
-            .idx
-            .buyer
-            .prefix(trader_addr.clone())
-            .range(storage, None, range_from.clone(), Order::Descending)
-            .take(limit)
-            .filter_map(|item| item.ok().map(|(_, trade)| trade))
-            .collect();
+    /// Returns the trades of the trader matching the filter, along with the last scanned trade
+    /// id when there may be more. The scan stops after MAX_SCANNED_TRADES, so a page can hold
+    /// fewer trades than the limit while later trades still match.
+    pub fn trades_filtered(
+        storage: &dyn Storage,
+        trader: Addr,
+        filter: &TradesFilter,
+        start_after: Option<u64>,
+        limit: usize,
+        block_time: u64,
+    ) -> StdResult<(Vec<Trade>, Option<u64>)> {
+        let range_to = start_after.map(Bound::exclusive);
+
+        // Filters are applied before the limit, so the cursor never skips trades
+        let mut result = vec![];
+        let mut scanned = 0;
+        for item in TRADE_PARTICIPANTS.prefix(&trader).range(
+            storage,
+            None,
+            range_to,
+            Order::Descending,
+        ) {
+            let (trade_id, _) = item?;
+            let trade = trades().load(storage, trade_id)?;
+            if filter.matches(&trade, block_time) {
+                result.push(trade);
+            }
+            scanned += 1;
+            if result.len() >= limit || scanned >= MAX_SCANNED_TRADES {
+                return Ok((result, Some(trade_id)));
+            }
+        }
+
+        Ok((result, None))
+    }
 
-        // Get trades where user is seller
-        let seller_trades: Vec<Trade> = trades()
+    /// Trades that are past their expiration but weren't expired in storage yet, oldest first.
+    pub fn expired_trades(
+        storage: &dyn Storage,
+        block_time: u64,
+        limit: usize,
+    ) -> StdResult<Vec<Trade>> {
+        // Non expirable trades are indexed under 0
+        trades()
             .idx
-            .seller
-            .prefix(trader_addr)
-            .range(storage, None, range_from, Order::Descending)
+            .expiration
+            .range(
+                storage,
+                Some(Bound::inclusive((1u64, 0u64))),
+                Some(Bound::exclusive((block_time, 0u64))),
+                Order::Ascending,
+            )
             .take(limit)
-            .filter_map(|item| item.ok().map(|(_, trade)| trade))
-            .collect();
+            .map(|item| item.map(|(_, trade)| trade))
+            .collect()
+    }
 
-        // Combine and sort by ID (descending)
-        let mut all_trades = buyer_trades;
-        all_trades.extend(seller_trades);
-        all_trades.sort_by(|a, b| b.id.cmp(&a.id));
-        all_trades.dedup_by(|a, b| a.id == b.id);
-        all_trades.truncate(limit);
+    /// Saves every stored trade again to populate the expiration index, used by migrations.
+    pub fn backfill_expiration(storage: &mut dyn Storage) -> StdResult<()> {
+        let all_trades: Vec<Trade> = trades()
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .map(|(_, trade)| trade)
+            .collect();
+        for trade in all_trades {
+            trades().save(storage, trade.id, &trade)?;
+        }
+        Ok(())
+    }
 
-        Ok(all_trades)
+    /// Sets the fees sent on top of the amount by makers that funded an escrow before the fees
+    /// were stored on the trade, used by migrations. Only trades still holding an escrow are
+    /// updated.
+    pub fn backfill_escrow_fees(
+        storage: &mut dyn Storage,
+        maker_fees: impl Fn(&Trade) -> Uint128,
+    ) -> StdResult<()> {
+        let escrow_states = [
+            TradeState::EscrowFunded,
+            TradeState::FiatDeposited,
+            TradeState::EscrowDisputed,
+            TradeState::DisputeDecided,
+        ];
+        let funded_trades: Vec<Trade> = trades()
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .map(|(_, trade)| trade)
+            .filter(|trade| escrow_states.contains(&trade.state) && trade.escrow_fees.is_zero())
+            .collect();
+        for mut trade in funded_trades {
+            // Requests accepted by the maker of a buy offer are funded by the taker, without fees
+            let funded_by_maker = !trade
+                .state_history
+                .iter()
+                .any(|item| item.state.eq(&TradeState::RequestAccepted));
+            if funded_by_maker {
+                trade.escrow_fees = maker_fees(&trade);
+                trades().save(storage, trade.id, &trade)?;
+            }
+        }
+        Ok(())
     }
 
     pub fn trades_by_arbitrator(
@@ -484,13 +1227,15 @@ impl TradeModel<'_> {
 
         let trade_states = [TradeState::EscrowDisputed,
             TradeState::SettledForMaker,
-            TradeState::SettledForTaker];
+            TradeState::SettledForTaker,
+            TradeState::SettledSplit,
+            TradeState::DisputeDecided];
 
-        let result = trades()
+        let mut result: Vec<Trade> = trades()
             .idx
             .arbitrator
-            .prefix(arbitrator)
-            .range(storage, None, range_from, Order::Descending)
+            .prefix(arbitrator.clone())
+            .range(storage, None, range_from.clone(), Order::Descending)
             .take(limit)
             .filter_map(|item| {
                 item.map(|(_, trade)| {
@@ -504,6 +1249,21 @@ impl TradeModel<'_> {
             })
             .collect();
 
+        // Panel members other than the first one are only found through the panels map
+        let arbitrator = Addr::unchecked(arbitrator);
+        for item in DISPUTE_PANELS
+            .prefix(&arbitrator)
+            .range(storage, None, range_from, Order::Descending)
+            .take(limit)
+        {
+            let (trade_id, _) = item?;
+            if !result.iter().any(|trade| trade.id.eq(&trade_id)) {
+                result.push(trades().load(storage, trade_id)?);
+            }
+        }
+        result.sort_by_key(|trade| std::cmp::Reverse(trade.id));
+        result.truncate(limit);
+
         Ok(result)
     }
 }
@@ -514,11 +1274,18 @@ pub struct TradeIndexes<'a> {
     pub arbitrator: MultiIndex<'a, String, Trade, u64>,
     pub buyer: MultiIndex<'a, Addr, Trade, u64>,
     pub seller: MultiIndex<'a, Addr, Trade, u64>,
+    pub expiration: MultiIndex<'a, u64, Trade, u64>,
 }
 
 impl<'a> IndexList<Trade> for TradeIndexes<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Trade>> + '_> {
-        let v: Vec<&dyn Index<Trade>> = vec![&self.collection, &self.arbitrator, &self.buyer, &self.seller];
+        let v: Vec<&dyn Index<Trade>> = vec![
+            &self.collection,
+            &self.arbitrator,
+            &self.buyer,
+            &self.seller,
+            &self.expiration,
+        ];
         Box::new(v.into_iter())
     }
 }
@@ -528,7 +1295,7 @@ pub fn trades() -> IndexedMap<u64, Trade, TradeIndexes<'static>> {
     let indexes = TradeIndexes {
         collection: UniqueIndex::new(|t| t.id, "trades__collection"),
         arbitrator: MultiIndex::new(
-            |_, t| t.arbitrator.to_string(),
+            |_, t| t.arbitrator.as_ref().map(Addr::to_string).unwrap_or_default(),
             pk_namespace,
             "trades__arbitrator",
         ),
@@ -542,6 +1309,11 @@ pub fn trades() -> IndexedMap<u64, Trade, TradeIndexes<'static>> {
             pk_namespace,
             "trades__seller",
         ),
+        expiration: MultiIndex::new(
+            |_, t| if t.is_expirable() { t.expires_at } else { 0 },
+            pk_namespace,
+            "trades__expiration",
+        ),
     };
     IndexedMap::new(pk_namespace, indexes)
 }
@@ -592,15 +1364,116 @@ impl ArbitratorModel {
         Ok(result)
     }
 
-    pub fn query_arbitrators(storage: &dyn Storage) -> StdResult<Vec<Arbitrator>> {
-        let result = arbitrators()
-            .range(storage, None, None, Order::Descending)
-            .flat_map(|item| item.map(|(_, arbitrator)| arbitrator))
-            .collect();
+    /// Lists the arbitrators by address, of every fiat or of the given one.
+    pub fn query_arbitrators(
+        storage: &dyn Storage,
+        fiat: Option<FiatCurrency>,
+        start_after: Option<(Addr, FiatCurrency)>,
+        limit: u32,
+    ) -> StdResult<Vec<Arbitrator>> {
+        let limit = validate_min_max_items_per_page(limit) as usize;
+        // The primary key is the address followed by the fiat
+        let cursor = start_after.map(|(addr, fiat)| addr.to_string() + &fiat.to_string());
+        let result = match fiat {
+            Some(fiat) => arbitrators()
+                .idx
+                .fiat
+                .prefix(fiat.to_string())
+                .range(
+                    storage,
+                    cursor.map(|pk| Bound::exclusive(pk.into_bytes())),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|item| item.map(|(_, arbitrator)| arbitrator))
+                .collect::<StdResult<Vec<Arbitrator>>>()?,
+            None => arbitrators()
+                .range(
+                    storage,
+                    cursor.as_deref().map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|item| item.map(|(_, arbitrator)| arbitrator))
+                .collect::<StdResult<Vec<Arbitrator>>>()?,
+        };
 
         Ok(result)
     }
 
+    pub fn query_applications(
+        storage: &dyn Storage,
+        start_after: Option<Addr>,
+        limit: u32,
+    ) -> StdResult<Vec<ArbitratorApplication>> {
+        let limit = validate_min_max_items_per_page(limit) as usize;
+        ARBITRATOR_APPLICATIONS
+            .range(
+                storage,
+                start_after.as_ref().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| item.map(|(_, application)| application))
+            .collect()
+    }
+
+    pub fn query_bond(
+        storage: &dyn Storage,
+        arbitrator: Addr,
+    ) -> StdResult<Option<ArbitratorBond>> {
+        ARBITRATOR_BONDS.may_load(storage, &arbitrator)
+    }
+
+    /// Removes the arbitrator from every fiat, it won't be drawn for new disputes.
+    pub fn remove_arbitrator(storage: &mut dyn Storage, arbitrator: &Addr) -> StdResult<()> {
+        let keys: Vec<String> = ArbitratorModel::query_arbitrator(storage, arbitrator.clone())?
+            .iter()
+            .map(|entry| entry.arbitrator.to_string() + &entry.fiat.to_string())
+            .collect();
+        for key in keys {
+            arbitrators().remove(storage, &key)?;
+        }
+        Ok(())
+    }
+
+    pub fn key_version(storage: &dyn Storage, arbitrator: &Addr) -> u32 {
+        ARBITRATOR_KEY_VERSIONS
+            .may_load(storage, arbitrator)
+            .unwrap_or_default()
+            .unwrap_or(0)
+    }
+
+    pub fn availability(storage: &dyn Storage, arbitrator: &Addr) -> ArbitratorAvailability {
+        ARBITRATOR_AVAILABILITY
+            .may_load(storage, arbitrator)
+            .unwrap_or_default()
+            .unwrap_or_default()
+    }
+
+    pub fn stats(storage: &dyn Storage, arbitrator: &Addr) -> ArbitratorStats {
+        ARBITRATOR_STATS
+            .may_load(storage, arbitrator)
+            .unwrap_or_default()
+            .unwrap_or_default()
+    }
+
+    pub fn update_stats(
+        storage: &mut dyn Storage,
+        arbitrators: &[Addr],
+        update: impl Fn(&mut ArbitratorStats),
+    ) -> StdResult<()> {
+        for arbitrator in arbitrators {
+            let mut stats = ArbitratorModel::stats(storage, arbitrator);
+            update(&mut stats);
+            ARBITRATOR_STATS.save(storage, arbitrator, &stats)?;
+        }
+        Ok(())
+    }
+
     pub fn query_arbitrators_fiat(
         storage: &dyn Storage,
         fiat: FiatCurrency,
@@ -610,36 +1483,172 @@ impl ArbitratorModel {
             .fiat
             .prefix(fiat.clone().to_string())
             .range(storage, None, None, Order::Descending)
-            .take(10)
             .flat_map(|item| item.map(|(_, arbitrator)| arbitrator))
             .collect();
 
         Ok(result)
     }
 
+    pub fn query_conflicts(storage: &dyn Storage, user: Addr) -> StdResult<Vec<Addr>> {
+        Ok(ARBITRATOR_CONFLICTS
+            .may_load(storage, &user)?
+            .unwrap_or_default())
+    }
+
+    pub fn open_disputes(storage: &dyn Storage, arbitrator: &Addr) -> u32 {
+        ARBITRATOR_OPEN_DISPUTES
+            .may_load(storage, arbitrator)
+            .unwrap_or_default()
+            .unwrap_or(0)
+    }
+
+    /// Counts a dispute in or out of the load of its arbitrators.
+    pub fn update_open_disputes(
+        storage: &mut dyn Storage,
+        arbitrators: &[Addr],
+        opened: bool,
+    ) -> StdResult<()> {
+        for arbitrator in arbitrators {
+            let open_disputes = ArbitratorModel::open_disputes(storage, arbitrator);
+            let open_disputes = if opened {
+                open_disputes + 1
+            } else {
+                open_disputes.saturating_sub(1)
+            };
+            ARBITRATOR_OPEN_DISPUTES.save(storage, arbitrator, &open_disputes)?;
+        }
+        Ok(())
+    }
+
     pub fn get_arbitrator_random<T: CustomQuery>(
         deps: Deps<T>,
         random_value: usize,
         fiat: FiatCurrency,
-    ) -> Arbitrator {
+    ) -> Option<Arbitrator> {
         assert_range_0_to_99(random_value).unwrap();
-        let storage = deps.storage;
-        let result: Vec<Arbitrator> = arbitrators()
+        ArbitratorModel::get_arbitrator_random_excluding(deps, random_value as u64, fiat, &[])
+    }
+
+    /// The arbitrators of the fiat a dispute can be drawn from, weighted by their
+    /// `selection_weight`. Only available arbitrators with room for another dispute qualify.
+    pub fn dispute_candidates(
+        storage: &dyn Storage,
+        fiat: &FiatCurrency,
+        excluded: &[Addr],
+    ) -> Vec<DisputeCandidate> {
+        arbitrators()
             .idx
             .fiat
             .prefix(fiat.to_string())
-            .range(storage, None, None, Order::Descending)
-            .take(10)
+            .range(storage, None, None, Order::Ascending)
             .flat_map(|item| item.map(|(_, arbitrator)| arbitrator))
+            .filter(|arbitrator| !excluded.contains(&arbitrator.arbitrator))
+            .filter_map(|arbitrator| {
+                let open_disputes = ArbitratorModel::open_disputes(storage, &arbitrator.arbitrator);
+                ArbitratorModel::availability(storage, &arbitrator.arbitrator)
+                    .accepts_disputes(open_disputes)
+                    .then(|| DisputeCandidate {
+                        weight: arbitrator.selection_weight(open_disputes),
+                        arbitrator: arbitrator.arbitrator,
+                    })
+            })
+            .filter(|candidate| candidate.weight > 0)
+            .collect()
+    }
+
+    /// Picks a random candidate that isn't one of the excluded addresses, weighted by weight.
+    pub fn pick_candidate(
+        candidates: &[DisputeCandidate],
+        random_value: u64,
+        excluded: &[Addr],
+    ) -> Option<Addr> {
+        let candidates: Vec<&DisputeCandidate> = candidates
+            .iter()
+            .filter(|candidate| !excluded.contains(&candidate.arbitrator))
             .collect();
-        let arbitrator_count = result.len();
-
-        // Random range: 0..99
-        // Mapped range: 0..result.len()-1
-        // Formula is:
-        // RandomValue * (MaxMappedRange + 1) / (MaxRandomRange + 1)
-        let random_index = random_value * arbitrator_count / (99 + 1);
-        result[random_index].clone()
+        let total_weight: u64 = candidates.iter().map(|candidate| candidate.weight).sum();
+        if total_weight == 0 {
+            return None;
+        }
+
+        // Walks the cumulative weights until the random point falls into one of them
+        let mut point = random_value % total_weight;
+        for candidate in candidates {
+            if point < candidate.weight {
+                return Some(candidate.arbitrator.clone());
+            }
+            point -= candidate.weight;
+        }
+        None
+    }
+
+    /// Picks a random arbitrator for the fiat that isn't one of the excluded addresses.
+    pub fn get_arbitrator_random_excluding<T: CustomQuery>(
+        deps: Deps<T>,
+        random_value: u64,
+        fiat: FiatCurrency,
+        excluded: &[Addr],
+    ) -> Option<Arbitrator> {
+        let candidates = ArbitratorModel::dispute_candidates(deps.storage, &fiat, excluded);
+        let arbitrator = ArbitratorModel::pick_candidate(&candidates, random_value, &[])?;
+        arbitrators()
+            .may_load(deps.storage, &(arbitrator.to_string() + &fiat.to_string()))
+            .ok()
+            .flatten()
+    }
+}
+
+pub struct DisputeEvidenceModel {}
+
+impl DisputeEvidenceModel {
+    pub fn count(storage: &dyn Storage, trade_id: u64) -> u32 {
+        DISPUTE_EVIDENCE_COUNT
+            .may_load(storage, trade_id)
+            .unwrap_or_default()
+            .unwrap_or(0)
+    }
+
+    /// The entries the author added to the evidence log of the trade.
+    pub fn author_count(storage: &dyn Storage, trade_id: u64, author: &Addr) -> u32 {
+        DISPUTE_EVIDENCE_AUTHOR_COUNT
+            .may_load(storage, (trade_id, author))
+            .unwrap_or_default()
+            .unwrap_or(0)
+    }
+
+    /// Appends the entry to the evidence log of the trade and returns it with its id.
+    pub fn append(
+        storage: &mut dyn Storage,
+        mut evidence: DisputeEvidence,
+    ) -> StdResult<DisputeEvidence> {
+        let trade_id = evidence.trade_id;
+        evidence.id = DisputeEvidenceModel::count(storage, trade_id);
+        DISPUTE_EVIDENCE.save(storage, (trade_id, evidence.id), &evidence)?;
+        DISPUTE_EVIDENCE_COUNT.save(storage, trade_id, &(evidence.id + 1))?;
+        let author = &evidence.author;
+        let author_count = DisputeEvidenceModel::author_count(storage, trade_id, author);
+        DISPUTE_EVIDENCE_AUTHOR_COUNT.save(storage, (trade_id, author), &(author_count + 1))?;
+        Ok(evidence)
+    }
+
+    pub fn query(
+        storage: &dyn Storage,
+        trade_id: u64,
+        start_after: Option<u32>,
+        limit: u32,
+    ) -> StdResult<Vec<DisputeEvidence>> {
+        let limit = validate_min_max_items_per_page(limit) as usize;
+        DISPUTE_EVIDENCE
+            .prefix(trade_id)
+            .range(
+                storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| item.map(|(_, evidence)| evidence))
+            .collect()
     }
 }
 